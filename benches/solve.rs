@@ -0,0 +1,100 @@
+//! Benchmarks solving and generating, run with `cargo bench`.
+//!
+//! The puzzles are in benches/corpus, one per line in the .sdm format, and must not change so that
+//! results stay comparable across commits:
+//! - easy.sdm and hard.sdm: generated by this crate with the seeds 1 to 10.
+//! - hardest.sdm: puzzles known to be hard for humans and solvers, e.g. Arto Inkala's "Everest",
+//!   "AI Escargot" and "Easter Monster".
+//! - 17-clue.sdm: puzzles with 17 givens, the fewest that a unique solution can have.
+
+use std::{hint::black_box, path::Path, time::Duration};
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+use sudoku::prelude::{
+    Difficulty, Puzzle, Rng, count_solutions, generate_with_difficulty_and_rng, generate_with_rng,
+    read_puzzles, solve, solve_any,
+};
+
+const CORPORA: [&str; 4] = ["easy", "hard", "hardest", "17-clue"];
+
+/// Reads the puzzles of a corpus in benches/corpus by name.
+fn corpus(name: &str) -> Vec<Puzzle> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("benches/corpus/{name}.sdm"));
+
+    read_puzzles(Some(path), None, true)
+        .expect("the corpus should be readable")
+        .value
+        .into_iter()
+        .map(|record| record.puzzle)
+        .collect()
+}
+
+/// Benchmarks a function on every puzzle of each corpus, with the time per puzzle.
+fn bench_corpora<T>(c: &mut Criterion, name: &str, f: impl Fn(Puzzle) -> T) {
+    let mut group = c.benchmark_group(name);
+
+    for corpus_name in CORPORA {
+        let puzzles = corpus(corpus_name);
+        group.throughput(Throughput::Elements(puzzles.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(corpus_name),
+            &puzzles,
+            |b, puzzles| {
+                b.iter(|| {
+                    for &puzzle in puzzles {
+                        black_box(f(black_box(puzzle)));
+                    }
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_solve_any(c: &mut Criterion) {
+    bench_corpora(c, "solve_any", solve_any);
+}
+
+fn bench_solve(c: &mut Criterion) {
+    bench_corpora(c, "solve", solve);
+}
+
+fn bench_unique(c: &mut Criterion) {
+    bench_corpora(c, "unique", |puzzle| count_solutions(puzzle, 2) == 1);
+}
+
+fn bench_generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate");
+    group
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(20));
+
+    // Fixed seeds, so that every run generates the same puzzles.
+    group.bench_function("random", |b| {
+        b.iter(|| generate_with_rng(&mut Rng::with_seed(black_box(1))));
+    });
+    for difficulty in [Difficulty::Easy, Difficulty::Hard] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(difficulty),
+            &difficulty,
+            |b, &difficulty| {
+                b.iter(|| {
+                    generate_with_difficulty_and_rng(difficulty, &mut Rng::with_seed(black_box(1)))
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_solve_any,
+    bench_solve,
+    bench_unique,
+    bench_generate
+);
+criterion_main!(benches);
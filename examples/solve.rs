@@ -1,12 +1,10 @@
-use std::path::Path;
-
 use anyhow::{Result, anyhow};
 
-use sudoku::prelude::{solve, write_grid};
+use sudoku::prelude::{Grid, GridStyle, render_grid, solve};
 
 fn main() -> Result<()> {
-    // Create a simple puzzle, which is a [[u8; 9]; 9] grid. Use 0 to represent blanks.
-    let puzzle = [
+    // Create a simple puzzle from a [[u8; 9]; 9] array. Use 0 to represent blanks.
+    let puzzle = Grid::try_from([
         [5, 3, 0, 0, 7, 0, 0, 0, 0],
         [6, 0, 0, 1, 0, 5, 0, 0, 0],
         [9, 8, 0, 0, 0, 0, 0, 6, 0],
@@ -16,7 +14,7 @@ fn main() -> Result<()> {
         [0, 6, 0, 0, 0, 0, 2, 8, 0],
         [0, 0, 0, 4, 0, 9, 0, 0, 5],
         [0, 0, 0, 0, 8, 0, 0, 7, 9],
-    ];
+    ])?;
 
     // Solve the puzzle.
     let solutions = solve(puzzle);
@@ -26,7 +24,7 @@ fn main() -> Result<()> {
 
     // Print the solutions.
     for solution in solutions {
-        write_grid(solution, None::<&Path>, true, " ")?;
+        print!("{}", render_grid(&solution, GridStyle::default()));
     }
 
     Ok(())
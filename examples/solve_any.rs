@@ -1,12 +1,10 @@
-use std::path::Path;
-
 use anyhow::{Result, anyhow};
 
-use sudoku::prelude::{solve_any, write_grid};
+use sudoku::prelude::{Grid, GridStyle, render_grid, solve_any};
 
 fn main() -> Result<()> {
-    // Create a simple puzzle, which is a [[u8; 9]; 9] grid. Use 0 to represent blanks.
-    let puzzle = [
+    // Create a simple puzzle from a [[u8; 9]; 9] array. Use 0 to represent blanks.
+    let puzzle = Grid::try_from([
         [5, 3, 0, 0, 7, 0, 0, 0, 0],
         [6, 0, 0, 1, 9, 5, 0, 0, 0],
         [9, 8, 0, 0, 0, 0, 0, 6, 0],
@@ -16,13 +14,13 @@ fn main() -> Result<()> {
         [0, 6, 0, 0, 0, 0, 2, 8, 0],
         [0, 0, 0, 4, 1, 9, 0, 0, 5],
         [0, 0, 0, 0, 8, 0, 0, 7, 9],
-    ];
+    ])?;
 
     // Solve the puzzle.
     let solution = solve_any(puzzle).ok_or(anyhow!("No solution."))?;
 
     // Print the solution.
-    write_grid(solution, None::<&Path>, true, " ")?;
+    print!("{}", render_grid(&solution, GridStyle::default()));
 
     Ok(())
 }
@@ -0,0 +1,36 @@
+//! Generates the C header of the C API into `$OUT_DIR/sudoku.h`.
+//!
+//! The checked-in `include/sudoku.h` is only updated when `SUDOKU_UPDATE_HEADER` is set, e.g.
+//! `SUDOKU_UPDATE_HEADER=1 cargo build -p sudoku-ffi`, and `tests/ffi.rs` checks that it is current.
+
+use std::{env, fs, path::PathBuf};
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-env-changed=SUDOKU_UPDATE_HEADER");
+
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        header: Some("/* The C API of the sudoku library, see ffi/src/lib.rs. */".into()),
+        autogen_warning: Some(
+            "/* Generated by build.rs with cbindgen, do not edit by hand. */".into(),
+        ),
+        include_guard: Some("SUDOKU_H".into()),
+        cpp_compat: true,
+        usize_is_size_t: true,
+        documentation: true,
+        ..Default::default()
+    };
+
+    let header = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("sudoku.h");
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src("src/lib.rs")
+        .generate()
+        .expect("the C API should be valid for cbindgen")
+        .write_to_file(&header);
+
+    if env::var_os("SUDOKU_UPDATE_HEADER").is_some() {
+        fs::copy(&header, "include/sudoku.h").expect("include/sudoku.h should be writable");
+    }
+}
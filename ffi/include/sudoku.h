@@ -0,0 +1,125 @@
+/* The C API of the sudoku library, see ffi/src/lib.rs. */
+
+#ifndef SUDOKU_H
+#define SUDOKU_H
+
+/* Generated by build.rs with cbindgen, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The call succeeded.
+ */
+#define SUDOKU_OK 0
+
+/**
+ * A pointer argument was null.
+ */
+#define SUDOKU_NULL_POINTER 1
+
+/**
+ * A grid had a cell that is not in 0-9.
+ */
+#define SUDOKU_INVALID_GRID 2
+
+/**
+ * The difficulty was not one of the `SUDOKU_DIFFICULTY_*` values.
+ */
+#define SUDOKU_INVALID_DIFFICULTY 3
+
+/**
+ * The puzzle has no solution.
+ */
+#define SUDOKU_NO_SOLUTION 4
+
+/**
+ * The library failed unexpectedly, this is a bug.
+ */
+#define SUDOKU_INTERNAL_ERROR 5
+
+/**
+ * Generating took longer than 10 seconds, which is rare. Another seed will likely be quicker.
+ */
+#define SUDOKU_TIMED_OUT 6
+
+/**
+ * Any difficulty, for [sudoku_generate].
+ */
+#define SUDOKU_DIFFICULTY_ANY 0
+
+/**
+ * Naked and hidden singles only.
+ */
+#define SUDOKU_DIFFICULTY_EASY 1
+
+/**
+ * Also locked candidates.
+ */
+#define SUDOKU_DIFFICULTY_MEDIUM 2
+
+/**
+ * Also naked and hidden pairs.
+ */
+#define SUDOKU_DIFFICULTY_HARD 3
+
+/**
+ * Needs more than the above, e.g. guessing.
+ */
+#define SUDOKU_DIFFICULTY_EXPERT 4
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Generates a puzzle with a unique solution into out.
+ *
+ * The same seed and difficulty always give the same puzzle, for the same version of the library.
+ * Use [SUDOKU_DIFFICULTY_ANY] for a puzzle of any difficulty, which is much faster. Gives up with
+ * [SUDOKU_TIMED_OUT] after 10 seconds.
+ *
+ * # Safety
+ *
+ * out must be null or point to 81 writable bytes.
+ */
+int32_t sudoku_generate(uint64_t seed, int32_t difficulty, uint8_t *out);
+
+/**
+ * Solves puzzle into out. If the puzzle has more than one solution, any of them is written.
+ *
+ * # Safety
+ *
+ * puzzle must be null or point to 81 readable bytes, and out must be null or point to 81 writable
+ * bytes. They may be the same buffer.
+ */
+int32_t sudoku_solve(const uint8_t *puzzle, uint8_t *out);
+
+/**
+ * Counts the solutions to puzzle into count, stopping at limit.
+ *
+ * # Safety
+ *
+ * puzzle must be null or point to 81 readable bytes, and count must be null or point to a
+ * writable `size_t`.
+ */
+int32_t sudoku_count(const uint8_t *puzzle, size_t limit, size_t *count);
+
+/**
+ * Rates puzzle into difficulty, as one of the `SUDOKU_DIFFICULTY_*` values other than any.
+ *
+ * # Safety
+ *
+ * puzzle must be null or point to 81 readable bytes, and difficulty must be null or point to a
+ * writable `int32_t`.
+ */
+int32_t sudoku_rate(const uint8_t *puzzle, int32_t *difficulty);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SUDOKU_H */
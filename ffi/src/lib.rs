@@ -0,0 +1,208 @@
+//! A C API for generate, solve, count and rate of the sudoku library, see `include/sudoku.h`.
+//!
+//! Grids are flat buffers of 81 bytes in reading order, with 1-9 for digits and 0 for blanks.
+//! Every function returns one of the `SUDOKU_*` status codes, and writes its result through an out
+//! pointer only when it returns [SUDOKU_OK]. Panics are caught, and never unwind into the caller.
+
+use std::{panic, ptr, slice, time::Duration};
+
+use sudoku::prelude::{
+    Budget, Difficulty, Grid, Rng, count_solutions, generate_with_budget,
+    generate_with_difficulty_and_budget, rate, solve_any,
+};
+
+/// The call succeeded.
+pub const SUDOKU_OK: i32 = 0;
+
+/// A pointer argument was null.
+pub const SUDOKU_NULL_POINTER: i32 = 1;
+
+/// A grid had a cell that is not in 0-9.
+pub const SUDOKU_INVALID_GRID: i32 = 2;
+
+/// The difficulty was not one of the `SUDOKU_DIFFICULTY_*` values.
+pub const SUDOKU_INVALID_DIFFICULTY: i32 = 3;
+
+/// The puzzle has no solution.
+pub const SUDOKU_NO_SOLUTION: i32 = 4;
+
+/// The library failed unexpectedly, this is a bug.
+pub const SUDOKU_INTERNAL_ERROR: i32 = 5;
+
+/// Generating took longer than 10 seconds, which is rare. Another seed will likely be quicker.
+pub const SUDOKU_TIMED_OUT: i32 = 6;
+
+/// The longest time [sudoku_generate] spends on a puzzle, see [SUDOKU_TIMED_OUT].
+const GENERATE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Any difficulty, for [sudoku_generate].
+pub const SUDOKU_DIFFICULTY_ANY: i32 = 0;
+
+/// Naked and hidden singles only.
+pub const SUDOKU_DIFFICULTY_EASY: i32 = 1;
+
+/// Also locked candidates.
+pub const SUDOKU_DIFFICULTY_MEDIUM: i32 = 2;
+
+/// Also naked and hidden pairs.
+pub const SUDOKU_DIFFICULTY_HARD: i32 = 3;
+
+/// Needs more than the above, e.g. guessing.
+pub const SUDOKU_DIFFICULTY_EXPERT: i32 = 4;
+
+/// Generates a puzzle with a unique solution into out.
+///
+/// The same seed and difficulty always give the same puzzle, for the same version of the library.
+/// Use [SUDOKU_DIFFICULTY_ANY] for a puzzle of any difficulty, which is much faster. Gives up with
+/// [SUDOKU_TIMED_OUT] after 10 seconds.
+///
+/// # Safety
+///
+/// out must be null or point to 81 writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sudoku_generate(seed: u64, difficulty: i32, out: *mut u8) -> i32 {
+    guard(|| {
+        if out.is_null() {
+            return SUDOKU_NULL_POINTER;
+        }
+        let difficulty = match difficulty {
+            SUDOKU_DIFFICULTY_ANY => None,
+            SUDOKU_DIFFICULTY_EASY => Some(Difficulty::Easy),
+            SUDOKU_DIFFICULTY_MEDIUM => Some(Difficulty::Medium),
+            SUDOKU_DIFFICULTY_HARD => Some(Difficulty::Hard),
+            SUDOKU_DIFFICULTY_EXPERT => Some(Difficulty::Expert),
+            _ => return SUDOKU_INVALID_DIFFICULTY,
+        };
+
+        let mut rng = Rng::with_seed(seed);
+        let budget = Budget::with_timeout(GENERATE_TIMEOUT);
+        let puzzle = match difficulty {
+            Some(difficulty) => generate_with_difficulty_and_budget(difficulty, &mut rng, &budget),
+            None => generate_with_budget(&mut rng, &budget),
+        };
+        let Ok(puzzle) = puzzle else {
+            return SUDOKU_TIMED_OUT;
+        };
+
+        // Safety: out is not null, and points to 81 bytes.
+        unsafe { write_grid(&puzzle, out) };
+        SUDOKU_OK
+    })
+}
+
+/// Solves puzzle into out. If the puzzle has more than one solution, any of them is written.
+///
+/// # Safety
+///
+/// puzzle must be null or point to 81 readable bytes, and out must be null or point to 81 writable
+/// bytes. They may be the same buffer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sudoku_solve(puzzle: *const u8, out: *mut u8) -> i32 {
+    guard(|| {
+        if out.is_null() {
+            return SUDOKU_NULL_POINTER;
+        }
+        // Safety: puzzle is null or points to 81 bytes.
+        let puzzle = match unsafe { read_grid(puzzle) } {
+            Ok(puzzle) => puzzle,
+            Err(status) => return status,
+        };
+
+        match solve_any(puzzle) {
+            Some(solution) => {
+                // Safety: out is not null, and points to 81 bytes.
+                unsafe { write_grid(&solution, out) };
+                SUDOKU_OK
+            }
+            None => SUDOKU_NO_SOLUTION,
+        }
+    })
+}
+
+/// Counts the solutions to puzzle into count, stopping at limit.
+///
+/// # Safety
+///
+/// puzzle must be null or point to 81 readable bytes, and count must be null or point to a
+/// writable `size_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sudoku_count(puzzle: *const u8, limit: usize, count: *mut usize) -> i32 {
+    guard(|| {
+        if count.is_null() {
+            return SUDOKU_NULL_POINTER;
+        }
+        // Safety: puzzle is null or points to 81 bytes.
+        let puzzle = match unsafe { read_grid(puzzle) } {
+            Ok(puzzle) => puzzle,
+            Err(status) => return status,
+        };
+
+        // Safety: count is not null.
+        unsafe { count.write(count_solutions(puzzle, limit)) };
+        SUDOKU_OK
+    })
+}
+
+/// Rates puzzle into difficulty, as one of the `SUDOKU_DIFFICULTY_*` values other than any.
+///
+/// # Safety
+///
+/// puzzle must be null or point to 81 readable bytes, and difficulty must be null or point to a
+/// writable `int32_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sudoku_rate(puzzle: *const u8, difficulty: *mut i32) -> i32 {
+    guard(|| {
+        if difficulty.is_null() {
+            return SUDOKU_NULL_POINTER;
+        }
+        // Safety: puzzle is null or points to 81 bytes.
+        let puzzle = match unsafe { read_grid(puzzle) } {
+            Ok(puzzle) => puzzle,
+            Err(status) => return status,
+        };
+
+        let rating = match rate(puzzle) {
+            Difficulty::Easy => SUDOKU_DIFFICULTY_EASY,
+            Difficulty::Medium => SUDOKU_DIFFICULTY_MEDIUM,
+            Difficulty::Hard => SUDOKU_DIFFICULTY_HARD,
+            Difficulty::Expert => SUDOKU_DIFFICULTY_EXPERT,
+        };
+        // Safety: difficulty is not null.
+        unsafe { difficulty.write(rating) };
+        SUDOKU_OK
+    })
+}
+
+/// Runs the body of an exported function, turning a panic into [SUDOKU_INTERNAL_ERROR].
+fn guard(body: impl FnOnce() -> i32 + panic::UnwindSafe) -> i32 {
+    panic::catch_unwind(body).unwrap_or(SUDOKU_INTERNAL_ERROR)
+}
+
+/// Reads a [Grid] from a buffer of 81 bytes.
+///
+/// # Safety
+///
+/// buffer must be null or point to 81 readable bytes.
+unsafe fn read_grid(buffer: *const u8) -> Result<Grid, i32> {
+    if buffer.is_null() {
+        return Err(SUDOKU_NULL_POINTER);
+    }
+    // Safety: buffer is not null, and points to 81 bytes.
+    let cells = unsafe { slice::from_raw_parts(buffer, 81) };
+
+    Grid::new(std::array::from_fn(|row| {
+        std::array::from_fn(|col| cells[row * 9 + col])
+    }))
+    .ok_or(SUDOKU_INVALID_GRID)
+}
+
+/// Writes a [Grid] to a buffer of 81 bytes.
+///
+/// # Safety
+///
+/// buffer must not be null, and point to 81 writable bytes.
+unsafe fn write_grid(grid: &Grid, buffer: *mut u8) {
+    let cells = grid.as_array().as_flattened();
+    // Safety: buffer points to 81 bytes, and may overlap the input when solving in place.
+    unsafe { ptr::copy(cells.as_ptr(), buffer, cells.len()) };
+}
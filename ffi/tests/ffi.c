@@ -0,0 +1,68 @@
+/* Exercises the C API, run by tests/ffi.rs. Exits with 0 when every check passes. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "sudoku.h"
+
+#define CHECK(condition)                                                  \
+    do {                                                                  \
+        if (!(condition)) {                                               \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+                    #condition);                                          \
+            return 1;                                                     \
+        }                                                                 \
+    } while (0)
+
+static void parse(const char *text, uint8_t *grid) {
+    for (int i = 0; i < 81; i++) {
+        grid[i] = text[i] == '.' ? 0 : (uint8_t)(text[i] - '0');
+    }
+}
+
+int main(void) {
+    uint8_t puzzle[81], solution[81], first[81], second[81];
+    size_t count = 0;
+    int32_t difficulty = 0;
+
+    parse("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+          puzzle);
+
+    CHECK(sudoku_solve(puzzle, solution) == SUDOKU_OK);
+    parse("534678912672195348198342567859761423426853791713924856961537284287419635345286179",
+          first);
+    CHECK(memcmp(solution, first, 81) == 0);
+
+    CHECK(sudoku_count(puzzle, 10, &count) == SUDOKU_OK);
+    CHECK(count == 1);
+    CHECK(sudoku_rate(puzzle, &difficulty) == SUDOKU_OK);
+    CHECK(difficulty == SUDOKU_DIFFICULTY_EASY);
+
+    /* The same seed gives the same puzzle. */
+    CHECK(sudoku_generate(42, SUDOKU_DIFFICULTY_ANY, first) == SUDOKU_OK);
+    CHECK(sudoku_generate(42, SUDOKU_DIFFICULTY_ANY, second) == SUDOKU_OK);
+    CHECK(memcmp(first, second, 81) == 0);
+    CHECK(sudoku_count(first, 2, &count) == SUDOKU_OK);
+    CHECK(count == 1);
+    CHECK(sudoku_generate(7, SUDOKU_DIFFICULTY_EASY, first) == SUDOKU_OK);
+    CHECK(sudoku_rate(first, &difficulty) == SUDOKU_OK);
+    CHECK(difficulty == SUDOKU_DIFFICULTY_EASY);
+
+    /* Solving in place. */
+    memcpy(first, puzzle, 81);
+    CHECK(sudoku_solve(first, first) == SUDOKU_OK);
+    CHECK(memcmp(first, solution, 81) == 0);
+
+    /* Errors. */
+    CHECK(sudoku_generate(1, 9, first) == SUDOKU_INVALID_DIFFICULTY);
+    CHECK(sudoku_generate(1, SUDOKU_DIFFICULTY_ANY, NULL) == SUDOKU_NULL_POINTER);
+    CHECK(sudoku_solve(NULL, first) == SUDOKU_NULL_POINTER);
+    CHECK(sudoku_rate(puzzle, NULL) == SUDOKU_NULL_POINTER);
+    puzzle[1] = 5;
+    CHECK(sudoku_solve(puzzle, first) == SUDOKU_NO_SOLUTION);
+    puzzle[1] = 10;
+    CHECK(sudoku_count(puzzle, 10, &count) == SUDOKU_INVALID_GRID);
+
+    printf("ok\n");
+    return 0;
+}
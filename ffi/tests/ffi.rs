@@ -0,0 +1,64 @@
+//! Checks that `include/sudoku.h` is current, and compiles `tests/ffi.c` against it and the shared
+//! library, and runs it.
+//!
+//! Needs a C compiler, `cc` or the one in `CC`.
+
+use std::{env, fs, path::PathBuf, process::Command};
+
+#[test]
+fn check_header_is_current() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let header = fs::read_to_string(manifest_dir.join("include/sudoku.h")).unwrap();
+
+    assert!(
+        header == include_str!(concat!(env!("OUT_DIR"), "/sudoku.h")),
+        "include/sudoku.h is out of date, update it with `SUDOKU_UPDATE_HEADER=1 cargo build -p sudoku-ffi`"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn check_c_api() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // The test binary is in target/<profile>/deps, and the shared library in target/<profile>.
+    let lib_dir = env::current_exe()
+        .unwrap()
+        .parent()
+        .and_then(|deps| deps.parent())
+        .unwrap()
+        .to_path_buf();
+    let program = lib_dir.join("ffi_test");
+
+    // cargo test only builds the rlib, so build the shared library as well.
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo.current_dir(&manifest_dir).args(["build", "--lib"]);
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    assert!(
+        cargo.status().unwrap().success(),
+        "cannot build the library"
+    );
+
+    let compiler = env::var("CC").unwrap_or("cc".into());
+    let status = Command::new(&compiler)
+        .arg(manifest_dir.join("tests/ffi.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .args(["-lsudoku_ffi", "-Wall", "-Werror", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap_or_else(|error| panic!("cannot run {compiler}: {error}"));
+    assert!(status.success(), "cannot compile tests/ffi.c");
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+}
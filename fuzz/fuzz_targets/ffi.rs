@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sudoku_ffi::{sudoku_count, sudoku_rate, sudoku_solve};
+
+// Any 81 bytes through the C API, which checks them itself.
+fuzz_target!(|cells: [u8; 81]| {
+    let mut out = [0; 81];
+    let mut count = 0;
+    let mut difficulty = 0;
+
+    // Safety: every pointer is to 81 bytes, or to a single value.
+    unsafe {
+        sudoku_solve(cells.as_ptr(), out.as_mut_ptr());
+        sudoku_count(cells.as_ptr(), 2, &mut count);
+        sudoku_rate(cells.as_ptr(), &mut difficulty);
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sudoku::prelude::{Move, read_game_from};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(mut game) = read_game_from(data) {
+        let _ = (game.board(), game.pencil_marks(), game.is_solved());
+        while game.play(Move::Undo).is_ok() {}
+        while game.play(Move::Redo).is_ok() {}
+    }
+});
@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sudoku::prelude::FileFormat;
+use sudoku_fuzz::check_parse;
+
+fuzz_target!(|data: &[u8]| check_parse(data, Some(FileFormat::Sdk)));
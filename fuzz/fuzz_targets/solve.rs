@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sudoku::prelude::{Grid, PuzzleRecord, has_conflict};
+use sudoku_fuzz::check_record;
+
+// Any 81 bytes, most of which are not a valid grid, and any positions, most of which are not in it.
+fuzz_target!(|input: ([[u8; 9]; 9], Vec<(usize, usize)>)| {
+    let (cells, positions) = input;
+    let Ok(puzzle) = Grid::try_from(cells) else {
+        return;
+    };
+
+    for pos in (0..9).flat_map(|row| (0..9).map(move |col| (row, col))) {
+        let _ = has_conflict(&puzzle, pos);
+    }
+    for pos in positions {
+        let _ = has_conflict(&puzzle, pos);
+    }
+    check_record(&PuzzleRecord::from(puzzle));
+});
@@ -0,0 +1,41 @@
+//! Checks shared by the fuzz targets in fuzz_targets, run with e.g. `cargo +nightly fuzz run sdk`.
+//!
+//! None of these should ever panic, whatever the input.
+
+use sudoku::prelude::{
+    Budget, FileFormat, Grid, PuzzleRecord, count_solutions_with_budget, hint, rate,
+    read_puzzles_from, solve_any_with_budget, validate,
+};
+
+/// The most grids to visit per search, so that a puzzle with few givens does not stall the fuzzer.
+const MAX_NODES: u64 = 10_000;
+
+/// Parses data as format, or guesses the format if it is None, and checks every record read.
+pub fn check_parse(data: &[u8], format: Option<FileFormat>) {
+    for strict in [false, true] {
+        if let Ok(parsed) = read_puzzles_from(data, format, strict) {
+            parsed.value.iter().for_each(check_record);
+        }
+    }
+}
+
+/// Solves, counts, rates and validates a record, the same as the sudoku command would.
+pub fn check_record(record: &PuzzleRecord) {
+    check_puzzle(record.puzzle, record);
+    if let Some(solution) = record.solution {
+        check_puzzle(solution, record);
+    }
+}
+
+fn check_puzzle(puzzle: Grid, record: &PuzzleRecord) {
+    let budget = Budget {
+        max_nodes: Some(MAX_NODES),
+        ..Default::default()
+    };
+
+    let _ = solve_any_with_budget(puzzle, &record.constraints, &budget);
+    let _ = count_solutions_with_budget(puzzle, &record.constraints, 2, &budget);
+    let _ = validate(&puzzle, &record.constraints);
+    let _ = rate(puzzle);
+    let _ = hint(puzzle);
+}
@@ -0,0 +1,377 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::sudoku::{
+    Aborted, Budget, Grid, GridPos, PencilMarks, Puzzle, Solution, count_solutions_with_budget,
+    solve_any_with_budget,
+};
+
+/// The most grids to visit when checking that a puzzle has a unique solution, which is about a
+/// second, and a few times more than the hardest puzzles in benches/corpus need. Saved games are
+/// untrusted, and a grid with few givens and no solution can take hours to rule out.
+const MAX_NODES_TO_CHECK: u64 = 10_000_000;
+
+/// A change made by the player to a [Game].
+///
+/// Cells are given as (row, col), with both row and col in 0..=8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Move {
+    /// Fills in a digit (1-9), replacing any digit that was there.
+    Enter { pos: GridPos, digit: u8 },
+
+    /// Adds the pencil mark of a digit (1-9), or removes it if it is already there.
+    Mark { pos: GridPos, digit: u8 },
+
+    /// Removes the digit and the pencil marks of a cell.
+    Clear { pos: GridPos },
+
+    /// Takes back the last change.
+    Undo,
+
+    /// Makes the last change that was taken back again.
+    Redo,
+}
+
+/// A change to a cell, with its digit and pencil marks before and after, so it can be undone.
+#[derive(Clone, Copy, Debug)]
+struct Change {
+    pos: GridPos,
+    before: (u8, u16),
+    after: (u8, u16),
+}
+
+/// A puzzle being played.
+///
+/// Every [Move] that is played is kept in order, so a game can be saved as its puzzle and moves,
+/// and replayed to the same state, see [Game::replay].
+#[derive(Clone, Debug)]
+pub struct Game {
+    puzzle: Puzzle,
+    solution: Solution,
+    board: Grid,
+    pencil_marks: PencilMarks,
+    moves: Vec<Move>,
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+    mistakes: usize,
+    elapsed: Duration,
+    resumed: Option<Instant>,
+}
+
+impl Game {
+    /// Starts a game of a puzzle, which must have exactly one solution. The clock starts running
+    /// right away.
+    ///
+    /// A puzzle that takes too long to check for a unique solution will cause an error.
+    pub fn new(puzzle: Puzzle) -> Result<Self> {
+        let solution = unique_solution(puzzle, MAX_NODES_TO_CHECK)?;
+
+        Ok(Self {
+            puzzle,
+            solution,
+            board: puzzle,
+            pencil_marks: [[0; 9]; 9],
+            moves: Vec::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            mistakes: 0,
+            elapsed: Duration::ZERO,
+            resumed: Some(Instant::now()),
+        })
+    }
+
+    /// Starts a game of a puzzle, and plays the moves in order.
+    ///
+    /// The result only depends on the puzzle and the moves, except for the clock.
+    pub fn replay(puzzle: Puzzle, moves: &[Move]) -> Result<Self> {
+        let mut game = Self::new(puzzle)?;
+        for (index, &played) in moves.iter().enumerate() {
+            game.play(played)
+                .map_err(|error| anyhow!("{error} (move {})", index + 1))?;
+        }
+
+        Ok(game)
+    }
+
+    /// Plays a move, and adds it to the moves of the game.
+    ///
+    /// Filling in a digit that is not in the solution counts as a mistake, even if it is undone
+    /// later. Changing a given, changing a solved game, or having nothing to undo or redo will
+    /// cause an error, and the move is not added. Neither is a move that changes nothing, e.g.
+    /// entering the digit that is already there.
+    pub fn play(&mut self, played: Move) -> Result<()> {
+        match played {
+            Move::Enter { pos, digit } | Move::Mark { pos, digit } => {
+                self.check_cell(pos)?;
+                if !(1..=9).contains(&digit) {
+                    return Err(anyhow!("Invalid input: digit must be 1-9."));
+                }
+            }
+            Move::Clear { pos } => self.check_cell(pos)?,
+            Move::Undo | Move::Redo => {}
+        }
+
+        match played {
+            Move::Enter { pos, digit } => {
+                if !self.change(pos, (digit, self.pencil_marks[pos.0][pos.1])) {
+                    return Ok(());
+                }
+                if digit != self.solution[pos.0][pos.1] {
+                    self.mistakes += 1;
+                }
+            }
+            Move::Mark { pos, digit } => {
+                let marks = self.pencil_marks[pos.0][pos.1] ^ (1 << digit);
+                self.change(pos, (self.board[pos.0][pos.1], marks));
+            }
+            Move::Clear { pos } => {
+                if !self.change(pos, (0, 0)) {
+                    return Ok(());
+                }
+            }
+            Move::Undo => {
+                let change = self
+                    .undo
+                    .pop()
+                    .ok_or(anyhow!("Invalid input: nothing to undo."))?;
+                self.set(change.pos, change.before);
+                self.redo.push(change);
+            }
+            Move::Redo => {
+                let change = self
+                    .redo
+                    .pop()
+                    .ok_or(anyhow!("Invalid input: nothing to redo."))?;
+                self.set(change.pos, change.after);
+                self.undo.push(change);
+            }
+        }
+
+        self.moves.push(played);
+        Ok(())
+    }
+
+    /// The puzzle being played.
+    pub fn puzzle(&self) -> Puzzle {
+        self.puzzle
+    }
+
+    /// The unique solution to the puzzle.
+    pub fn solution(&self) -> Solution {
+        self.solution
+    }
+
+    /// The givens along with the digits filled in by the player.
+    pub fn board(&self) -> Grid {
+        self.board
+    }
+
+    /// The pencil marks made by the player.
+    pub fn pencil_marks(&self) -> PencilMarks {
+        self.pencil_marks
+    }
+
+    /// Every move played so far, in order.
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// The number of digits filled in that are not in the solution.
+    pub fn mistakes(&self) -> usize {
+        self.mistakes
+    }
+
+    /// Verifies whether the board matches the solution.
+    pub fn is_solved(&self) -> bool {
+        self.board == self.solution
+    }
+
+    /// The time spent playing, which stops while paused and once the game is solved.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+            + self
+                .resumed
+                .map_or(Duration::ZERO, |resumed| resumed.elapsed())
+    }
+
+    /// Sets the time spent playing, e.g. when a saved game is loaded.
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.elapsed = elapsed;
+        if self.resumed.is_some() {
+            self.resumed = Some(Instant::now());
+        }
+    }
+
+    /// Stops the clock.
+    pub fn pause(&mut self) {
+        self.elapsed = self.elapsed();
+        self.resumed = None;
+    }
+
+    /// Starts the clock again, unless the game is solved.
+    pub fn resume(&mut self) {
+        if self.resumed.is_none() && !self.is_solved() {
+            self.resumed = Some(Instant::now());
+        }
+    }
+
+    /// Verifies whether the cell at pos can be changed.
+    fn check_cell(&self, (row, col): GridPos) -> Result<()> {
+        if row >= 9 || col >= 9 {
+            Err(anyhow!(
+                "Invalid input: cell ({row}, {col}) is outside the grid."
+            ))
+        } else if self.puzzle[row][col] != 0 {
+            Err(anyhow!("Invalid input: givens cannot be changed."))
+        } else if self.is_solved() {
+            Err(anyhow!("Invalid input: the puzzle is already solved."))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Changes a cell, and records it for undo. Returns false if the cell is already as after.
+    fn change(&mut self, pos: GridPos, after: (u8, u16)) -> bool {
+        let before = (self.board[pos.0][pos.1], self.pencil_marks[pos.0][pos.1]);
+        if before == after {
+            return false;
+        }
+
+        self.set(pos, after);
+        self.undo.push(Change { pos, before, after });
+        self.redo.clear();
+        true
+    }
+
+    /// Sets the digit and pencil marks of a cell, and stops the clock if the game is solved, or
+    /// starts it again if the game is no longer solved, e.g. after an undo.
+    fn set(&mut self, (row, col): GridPos, (digit, marks): (u8, u16)) {
+        let was_solved = self.is_solved();
+        self.board.set((row, col), digit);
+        self.pencil_marks[row][col] = marks;
+
+        if self.is_solved() {
+            self.pause();
+        } else if was_solved {
+            self.resume();
+        }
+    }
+}
+
+/// Finds the solution to puzzle, which must have exactly one, visiting at most max_nodes grids in
+/// each search.
+fn unique_solution(puzzle: Puzzle, max_nodes: u64) -> Result<Solution> {
+    let budget = Budget {
+        max_nodes: Some(max_nodes),
+        ..Default::default()
+    };
+    let too_hard = |Aborted| {
+        anyhow!("Invalid input: the puzzle is too hard to check for exactly one solution.")
+    };
+
+    match count_solutions_with_budget(puzzle, &[], 2, &budget).map_err(too_hard)? {
+        1 => solve_any_with_budget(puzzle, &[], &budget)
+            .map_err(too_hard)?
+            .ok_or(anyhow!("Invalid input: the puzzle has no solution.")),
+        _ => Err(anyhow!(
+            "Invalid input: the puzzle must have exactly one solution."
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::solve_any;
+
+    #[test]
+    fn check_game() {
+        let solution = solve_any(Grid::EMPTY).unwrap();
+        let mut puzzle = solution;
+        puzzle.set((0, 0), 0);
+        puzzle.set((0, 1), 0);
+        let wrong = solution[0][1];
+
+        let mut game = Game::new(puzzle).unwrap();
+        assert!(Game::new(Grid::EMPTY).is_err());
+
+        game.play(Move::Enter {
+            pos: (0, 0),
+            digit: wrong,
+        })
+        .unwrap();
+        game.play(Move::Mark {
+            pos: (0, 1),
+            digit: 4,
+        })
+        .unwrap();
+        game.play(Move::Undo).unwrap();
+        game.play(Move::Undo).unwrap();
+        assert_eq!(game.board(), puzzle);
+        game.play(Move::Redo).unwrap();
+        assert_eq!(game.board()[0][0], wrong);
+        assert_eq!(game.pencil_marks()[0][1], 0);
+        assert_eq!(game.mistakes(), 1);
+
+        // Moves that change nothing are not kept, nor counted as mistakes again.
+        game.play(Move::Enter {
+            pos: (0, 0),
+            digit: wrong,
+        })
+        .unwrap();
+        game.play(Move::Clear { pos: (0, 1) }).unwrap();
+        assert_eq!(game.mistakes(), 1);
+
+        // Failed moves are not kept.
+        assert!(game.play(Move::Clear { pos: (1, 1) }).is_err());
+        assert!(game.play(Move::Clear { pos: (9, 0) }).is_err());
+        assert!(
+            game.play(Move::Enter {
+                pos: (0, 0),
+                digit: 0
+            })
+            .is_err()
+        );
+        assert_eq!(game.moves().len(), 5);
+
+        for (pos, digit) in [((0, 0), solution[0][0]), ((0, 1), solution[0][1])] {
+            game.play(Move::Enter { pos, digit }).unwrap();
+        }
+        assert!(game.is_solved());
+        assert!(game.play(Move::Clear { pos: (0, 0) }).is_err());
+
+        // The clock stops once solved.
+        let elapsed = game.elapsed();
+        game.resume();
+        assert_eq!(game.elapsed(), elapsed);
+
+        let replayed = Game::replay(puzzle, game.moves()).unwrap();
+        assert_eq!(replayed.board(), game.board());
+        assert_eq!(replayed.mistakes(), 1);
+        assert!(Game::replay(puzzle, &[Move::Redo]).is_err());
+
+        // The clock starts again once an undo makes the game unsolved.
+        game.play(Move::Undo).unwrap();
+        assert!(!game.is_solved());
+        assert!(game.resumed.is_some());
+        game.play(Move::Redo).unwrap();
+        assert!(game.resumed.is_none());
+    }
+
+    #[test]
+    fn check_game_of_slow_puzzle() {
+        // Found by the game fuzz target, which ran for minutes to rule out every solution. The
+        // limit is lower than in Game::new, which takes a while in a debug build.
+        let puzzle: Grid =
+            "9...................................41.8.56.....693.12.4..3.....8.15.74..5..49136"
+                .parse()
+                .unwrap();
+
+        let error = unique_solution(puzzle, 100_000).unwrap_err();
+        assert!(error.to_string().contains("too hard"), "{error}");
+        assert!(unique_solution(Grid::EMPTY, 100_000).is_err());
+    }
+}
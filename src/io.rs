@@ -1,12 +1,343 @@
+mod ansi;
+mod fpuzzles;
+mod lenient;
+mod lz_string;
+mod pdf;
+mod png;
+mod sadman;
+mod simple_sudoku;
+mod svg;
+
 use std::{
+    fmt::{self, Display, Formatter},
     fs::File,
     io::{self, BufReader, Read, Write},
     path::Path,
+    str::FromStr,
+    time::Duration,
 };
 
 use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{Game, Move},
+    sudoku::{Grid, GridPos, PencilMarks, Puzzle, Solution},
+    variant::Constraint,
+};
+
+/// A puzzle file format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileFormat {
+    /// A 9x9 grid in any of the layouts accepted by [read_to_grid].
+    Grid,
+
+    /// SadMan Software `.sdk`, a single puzzle with metadata.
+    Sdk,
+
+    /// SadMan Software `.sdm`, many puzzles, one 81-char puzzle per line.
+    Sdm,
+
+    /// Simple Sudoku `.ss`, a 9x9 grid with `|` and `-` separators.
+    Ss,
+
+    /// The crate's own JSON variant puzzle description, see [read_variant_puzzle].
+    Variant,
+
+    /// f-puzzles / SudokuPad JSON, see [read_fpuzzles].
+    Fpuzzles,
+
+    /// SVG image, see [write_svg]. It can only be written.
+    Svg,
+
+    /// PNG image, see [write_png]. It can only be written.
+    Png,
+}
+
+/// Optional information about a puzzle, as kept by some [FileFormat]s.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Metadata {
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub comment: Option<String>,
+    pub date: Option<String>,
+    pub source: Option<String>,
+    pub source_url: Option<String>,
+    pub difficulty: Option<String>,
+}
+
+/// A colour as red, green and blue.
+pub type Rgb = [u8; 3];
+
+/// The font of the digits in a PNG image, see [PngStyle].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum PngFont {
+    /// A blocky bitmap font, which needs no font file.
+    #[default]
+    Builtin,
+
+    /// The contents of a TrueType or OpenType font file.
+    TrueType(Vec<u8>),
+}
+
+/// How a PNG image is drawn, see [write_png].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PngStyle {
+    /// The width and height of a cell, in pixels (8-512).
+    pub cell_size: u32,
+    pub font: PngFont,
+    pub background_color: Rgb,
+    pub line_color: Rgb,
+    pub given_color: Rgb,
+    pub filled_color: Rgb,
+
+    /// The background of cells whose digit is repeated in their row, col or square.
+    pub conflict_color: Rgb,
+    pub highlight_conflicts: bool,
+}
+
+impl Default for PngStyle {
+    fn default() -> Self {
+        Self {
+            cell_size: 48,
+            font: PngFont::Builtin,
+            background_color: [255, 255, 255],
+            line_color: [0, 0, 0],
+            given_color: [0, 0, 0],
+            filled_color: [26, 95, 180],
+            conflict_color: [246, 190, 190],
+            highlight_conflicts: true,
+        }
+    }
+}
+
+/// How a [Grid] is written as text, see [render_grid].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridStyle<'a> {
+    /// Draws a border around each 3x3 digit square, making a 13x13 char grid instead of 9x9.
+    pub border: bool,
+
+    /// Replaces every 0 value in the grid.
+    pub blank_char: &'a str,
+}
+
+impl Default for GridStyle<'_> {
+    fn default() -> Self {
+        Self {
+            border: true,
+            blank_char: " ",
+        }
+    }
+}
+
+/// A puzzle, along with its [Constraint]s and [Metadata], as read from or written to a file.
+///
+/// With the `serde` feature, grids and pencil marks are in the compact forms of the `serialize`
+/// module.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PuzzleRecord {
+    pub puzzle: Puzzle,
+    pub constraints: Vec<Constraint>,
+    pub metadata: Metadata,
+
+    /// The solution, for formats that draw it along with the givens, see [FileFormat::is_image].
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub solution: Option<Solution>,
+
+    /// The candidates of every cell, if read from a grid of pencil marks.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            with = "crate::serialize::option_pencil_marks",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub pencil_marks: Option<PencilMarks>,
+}
+
+/// A warning about suspicious input, at a 1-based line and col.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub col: usize,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "line {}, col {}: {}", self.line, self.col, self.message)
+    }
+}
+
+/// A value parsed from input, along with any [Diagnostic]s about suspicious input that was
+/// accepted anyway.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Parsed<T> {
+    pub value: T,
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl From<Puzzle> for PuzzleRecord {
+    fn from(puzzle: Puzzle) -> Self {
+        Self {
+            puzzle,
+            ..Default::default()
+        }
+    }
+}
+
+impl FileFormat {
+    /// Guesses the format from the extension of a file name, if it is a well-known one.
+    pub fn from_extension<P: AsRef<Path>>(file: P) -> Option<FileFormat> {
+        let extension = file.as_ref().extension()?.to_str()?.to_ascii_lowercase();
 
-use crate::sudoku::Grid;
+        match extension.as_str() {
+            "sdk" => Some(FileFormat::Sdk),
+            "sdm" => Some(FileFormat::Sdm),
+            "ss" => Some(FileFormat::Ss),
+            "svg" => Some(FileFormat::Svg),
+            "png" => Some(FileFormat::Png),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the content.
+    ///
+    /// Content that cannot be recognised as any other format is assumed to be [FileFormat::Grid].
+    pub fn detect(content: &str) -> FileFormat {
+        let content = content.trim();
+        let lines = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+
+        if content.starts_with('{') {
+            // f-puzzles always has a size, which the variant description does not.
+            let is_fpuzzles = serde_json::from_str::<serde_json::Value>(content)
+                .is_ok_and(|json| json.get("size").is_some());
+
+            return if is_fpuzzles {
+                FileFormat::Fpuzzles
+            } else {
+                FileFormat::Variant
+            };
+        }
+
+        if content.starts_with("N4Ig")
+            || content.contains("f-puzzles.com")
+            || content.contains("sudokupad.app")
+        {
+            return FileFormat::Fpuzzles;
+        }
+
+        if lines
+            .first()
+            .is_some_and(|line| line.starts_with('[') || line.starts_with('#'))
+        {
+            return FileFormat::Sdk;
+        }
+
+        // A single line of 81 chars is just as likely a grid with other blanks, e.g. `_`.
+        if !lines.is_empty()
+            && lines.iter().all(|line| line.chars().count() == 81)
+            && (lines.len() > 1 || lines[0].chars().all(sadman::is_sdk_cell))
+        {
+            return FileFormat::Sdm;
+        }
+
+        let is_ss_line = |line: &&str| {
+            line.chars().count() == 11 && (line.contains('|') || line.chars().all(|c| c == '-'))
+        };
+        if lines.iter().any(is_ss_line) {
+            return FileFormat::Ss;
+        }
+
+        FileFormat::Grid
+    }
+
+    /// Verifies whether the format can hold more than one puzzle.
+    pub fn holds_many(self) -> bool {
+        self == FileFormat::Sdm
+    }
+
+    /// Verifies whether the format is an image, which draws the solution (if any) of a
+    /// [PuzzleRecord] over its givens.
+    pub fn is_image(self) -> bool {
+        matches!(self, FileFormat::Svg | FileFormat::Png)
+    }
+
+    /// Verifies whether the format can hold variant [Constraint]s.
+    pub fn holds_constraints(self) -> bool {
+        matches!(
+            self,
+            FileFormat::Grid | FileFormat::Variant | FileFormat::Fpuzzles | FileFormat::Svg
+        )
+    }
+}
+
+impl Display for FileFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FileFormat::Grid => "grid",
+            FileFormat::Sdk => "sdk",
+            FileFormat::Sdm => "sdm",
+            FileFormat::Ss => "ss",
+            FileFormat::Variant => "variant",
+            FileFormat::Fpuzzles => "fpuzzles",
+            FileFormat::Svg => "svg",
+            FileFormat::Png => "png",
+        })
+    }
+}
+
+impl FromStr for FileFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "grid" => Ok(FileFormat::Grid),
+            "sdk" => Ok(FileFormat::Sdk),
+            "sdm" => Ok(FileFormat::Sdm),
+            "ss" => Ok(FileFormat::Ss),
+            "variant" => Ok(FileFormat::Variant),
+            "fpuzzles" => Ok(FileFormat::Fpuzzles),
+            "svg" => Ok(FileFormat::Svg),
+            "png" => Ok(FileFormat::Png),
+            _ => Err(anyhow!("Unknown format: {s}.")),
+        }
+    }
+}
+
+/// A variant puzzle in the JSON description format.
+#[derive(Serialize, Deserialize)]
+struct VariantDescription {
+    /// The 81 cells in reading order, with digits 1-9 for givens and `.` or `0` for blanks.
+    grid: String,
+
+    /// The additional rules, see [Constraint].
+    #[serde(default)]
+    constraints: Vec<Constraint>,
+}
+
+/// A saved [Game] in the JSON save format.
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    /// The 81 cells of the puzzle in reading order, with digits 1-9 for givens and `.` or `0` for blanks.
+    grid: String,
+
+    /// The time spent playing, in milliseconds.
+    #[serde(default)]
+    elapsed_ms: u64,
+
+    /// Every move played, in order.
+    #[serde(default)]
+    moves: Vec<Move>,
+}
 
 /// Reads input content into a [Grid].
 ///
@@ -49,70 +380,51 @@ use crate::sudoku::Grid;
 /// |   | 8 | 79|
 /// +---+---+---+
 /// ```
+///
+/// Input is parsed leniently. The following are also accepted:
+///
+/// - All 81 cells on a single line.
+/// - Spaces between cells, e.g. `5 3 . . 7 . . . .`.
+/// - Other border styles, e.g. box-drawing chars.
+/// - Pencil marks, where every row has 9 groups of candidates and a single candidate is a given.
+/// - Blanks as any of ` `, `.`, `0`, `_`, `x` or `X`.
+///
+/// Any other char in a cell (e.g. `l` for `1`) is treated as a blank, see [read_to_grid_strict]
+/// to be warned about it.
 pub fn read_to_grid<P: AsRef<Path>>(input_file: Option<P>) -> Result<Grid> {
-    let mut buffer = String::new();
-    let mut reader: Box<dyn Read> = if let Some(input_file) = input_file {
-        Box::new(File::open(input_file)?)
-    } else {
-        Box::new(BufReader::new(io::stdin().lock()))
-    };
-    reader.read_to_string(&mut buffer)?;
-
-    let mut lines = buffer
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(String::from)
-        .collect::<Vec<_>>();
-    if lines.len() != 9 && lines.len() != 13 {
-        return Err(anyhow!("Invalid input: incorrect number of rows."));
-    }
-
-    if lines.len() == 13 {
-        if !lines.iter().all(|line| line.chars().count() == 13) {
-            return Err(anyhow!("Invalid input: incorrect row len."));
-        }
+    read_grid(open_input(input_file)?)
+}
 
-        // Extract the embedded digits from the grid.
+/// Reads the entire content of reader into a [Grid], in any of the layouts accepted by
+/// [read_to_grid].
+pub fn read_grid(reader: impl Read) -> Result<Grid> {
+    parse_grid(&read_all(reader)?)
+}
 
-        let extract_digits = |line: String| -> String {
-            line.chars()
-                .enumerate()
-                .filter_map(|(index, c)| {
-                    if index == 0 || index == 4 || index == 8 || index == 12 {
-                        None
-                    } else {
-                        Some(c)
-                    }
-                })
-                .collect()
-        };
+/// Parses content into a [Grid], in any of the layouts accepted by [read_to_grid].
+pub fn parse_grid(content: &str) -> Result<Grid> {
+    Ok(lenient::parse_grid_text(content, false)?.value.puzzle)
+}
 
-        lines = lines
-            .into_iter()
-            .enumerate()
-            .filter_map(|(index, line)| {
-                if index == 0 || index == 4 || index == 8 || index == 12 {
-                    None
-                } else {
-                    Some(extract_digits(line))
-                }
-            })
-            .collect::<Vec<_>>();
-    }
+/// Reads input content into a [Grid], the same as [read_to_grid], along with warnings about
+/// suspicious input, e.g. unexpected chars or rows that are too short.
+///
+/// If strict is true, then any warning becomes an error.
+pub fn read_to_grid_strict<P: AsRef<Path>>(
+    input_file: Option<P>,
+    strict: bool,
+) -> Result<Parsed<Grid>> {
+    read_grid_strict(open_input(input_file)?, strict)
+}
 
-    let mut grid = [[0; 9]; 9];
-    for (row, line) in lines.into_iter().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            if c.is_ascii_digit()
-                && let Some(digit) = c.to_digit(10)
-                && digit != 0
-            {
-                grid[row][col] = digit as u8;
-            }
-        }
-    }
+/// Reads the entire content of reader into a [Grid], the same as [read_to_grid_strict].
+pub fn read_grid_strict(reader: impl Read, strict: bool) -> Result<Parsed<Grid>> {
+    let parsed = lenient::parse_grid_text(&read_all(reader)?, strict)?;
 
-    Ok(grid)
+    Ok(Parsed {
+        value: parsed.value.puzzle,
+        warnings: parsed.warnings,
+    })
 }
 
 /// Writes a [Grid] to output.
@@ -129,20 +441,564 @@ pub fn write_grid<P: AsRef<Path>>(
     border: bool,
     blank_char: &str,
 ) -> Result<()> {
-    let output = if border {
-        grid_to_border_string(grid, blank_char)
+    write_grid_to(
+        &grid,
+        open_output(output_file)?,
+        GridStyle { border, blank_char },
+    )
+}
+
+/// Writes a [Grid] to writer, as rendered by [render_grid].
+pub fn write_grid_to(grid: &Grid, mut writer: impl Write, style: GridStyle) -> Result<()> {
+    writer.write_all(render_grid(grid, style).as_bytes())?;
+
+    Ok(())
+}
+
+/// Converts a [Grid] to text, in the layout of [write_grid]. The text ends with a newline char.
+pub fn render_grid(grid: &Grid, style: GridStyle) -> String {
+    if style.border {
+        grid_to_border_string(*grid, style.blank_char)
     } else {
-        grid_to_string(grid, blank_char)
+        grid_to_string(*grid, style.blank_char)
+    }
+}
+
+/// Writes a puzzle to output, in colour for a terminal.
+///
+/// If output_file is None, then output will be written to stdout.
+///
+/// The digits in filled (if any) are drawn over the blanks of the puzzle. Givens are bold, filled
+/// in digits are cyan, conflicting digits are red, and the cells in highlights have a yellow
+/// background. The layout is the same as [write_grid].
+pub fn write_grid_colored<P: AsRef<Path>>(
+    puzzle: Grid,
+    filled: Option<Grid>,
+    highlights: &[GridPos],
+    output_file: Option<P>,
+    border: bool,
+    blank_char: &str,
+) -> Result<()> {
+    write_grid_colored_to(
+        puzzle,
+        filled,
+        highlights,
+        open_output(output_file)?,
+        border,
+        blank_char,
+    )
+}
+
+/// Writes a puzzle to writer in colour, the same as [write_grid_colored].
+pub fn write_grid_colored_to(
+    puzzle: Grid,
+    filled: Option<Grid>,
+    highlights: &[GridPos],
+    mut writer: impl Write,
+    border: bool,
+    blank_char: &str,
+) -> Result<()> {
+    let output = ansi::grid_to_ansi(&puzzle, filled.as_ref(), highlights, border, blank_char);
+    writer.write_all(output.as_bytes())?;
+
+    Ok(())
+}
+
+/// Writes a [Grid] with edge clues to output.
+///
+/// If output_file is None, then output will be written to stdout.
+///
+/// The output is a 25x25 char grid, where every pair of adjacent digits is separated by a char
+/// that shows the edge clue between them, if any. Between two boxes, the clue is drawn just before
+/// the box border:
+///
+/// ```text
+/// ╔═══════╤═══════╤═══════╗
+/// ║       │       │       ║
+/// ║ 5 3○4<│ 6 7○8 │ 9X1 2 ║
+/// ║ ○   ● │     ∨ │   V   ║
+/// ║ 6 7 2 │ 1 9 5 │ 3 4 8 ║
+/// ```
+///
+/// Kropki dots are drawn as ○ (white) and ● (black), XV as X and V, and greater-than signs as <,
+/// >, ∧ and ∨ pointing at the smaller digit. Constraints that are not edge clues are ignored.
+///
+/// Any 0 value in the grid will be replaced by blank_char in the output.
+pub fn write_grid_with_edge_clues<P: AsRef<Path>>(
+    grid: Grid,
+    constraints: &[Constraint],
+    output_file: Option<P>,
+    blank_char: &str,
+) -> Result<()> {
+    write_grid_with_edge_clues_to(grid, constraints, open_output(output_file)?, blank_char)
+}
+
+/// Writes a [Grid] with edge clues to writer, the same as [write_grid_with_edge_clues].
+pub fn write_grid_with_edge_clues_to(
+    grid: Grid,
+    constraints: &[Constraint],
+    mut writer: impl Write,
+    blank_char: &str,
+) -> Result<()> {
+    let output = grid_to_edge_clue_string(grid, constraints, blank_char);
+    writer.write_all(output.as_bytes())?;
+
+    Ok(())
+}
+
+/// Reads a variant puzzle, described in JSON, into a [Puzzle] and its [Constraint]s.
+///
+/// If input_file is None, then input will be read from stdin.
+///
+/// Cells are given as [row, col], with both row and col in 0..=8. The cells in the grid string
+/// are in reading order. The types of constraint are thermometer, arrow, sandwich, white_dot,
+/// black_dot, x, v and greater_than. Example of accepted input:
+///
+/// ```json
+/// {
+///   "grid": "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+///   "constraints": [
+///     { "type": "thermometer", "cells": [[0, 2], [0, 3], [1, 4]] },
+///     { "type": "arrow", "circle": [[0, 6]], "arrow": [[0, 3], [0, 1]] },
+///     { "type": "sandwich", "line": { "col": 0 }, "sum": 19 },
+///     { "type": "white_dot", "cells": [[0, 1], [0, 2]] }
+///   ]
+/// }
+/// ```
+pub fn read_variant_puzzle<P: AsRef<Path>>(
+    input_file: Option<P>,
+) -> Result<(Puzzle, Vec<Constraint>)> {
+    read_variant_puzzle_from(open_input(input_file)?)
+}
+
+/// Reads the entire content of reader into a variant puzzle, the same as [read_variant_puzzle].
+pub fn read_variant_puzzle_from(reader: impl Read) -> Result<(Puzzle, Vec<Constraint>)> {
+    parse_variant(&read_all(reader)?)
+}
+
+/// Writes a variant puzzle to output, in the JSON description format read by
+/// [read_variant_puzzle].
+///
+/// If output_file is None, then output will be written to stdout.
+pub fn write_variant_puzzle<P: AsRef<Path>>(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    output_file: Option<P>,
+) -> Result<()> {
+    write_variant_puzzle_to(puzzle, constraints, open_output(output_file)?)
+}
+
+/// Writes a variant puzzle to writer, the same as [write_variant_puzzle].
+pub fn write_variant_puzzle_to(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    mut writer: impl Write,
+) -> Result<()> {
+    writer.write_all(variant_to_string(puzzle, constraints)?.as_bytes())?;
+
+    Ok(())
+}
+
+/// Reads a saved [Game], and replays its moves.
+///
+/// If input_file is None, then input will be read from stdin.
+///
+/// The input is the JSON save format written by [write_game], e.g.:
+///
+/// ```json
+/// {
+///   "grid": "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+///   "elapsed_ms": 61000,
+///   "moves": [
+///     {"type":"enter","pos":[4,4],"digit":5},
+///     {"type":"mark","pos":[0,2],"digit":4},
+///     {"type":"undo"}
+///   ]
+/// }
+/// ```
+pub fn read_game<P: AsRef<Path>>(input_file: Option<P>) -> Result<Game> {
+    read_game_from(open_input(input_file)?)
+}
+
+/// Reads the entire content of reader into a [Game], the same as [read_game].
+pub fn read_game_from(reader: impl Read) -> Result<Game> {
+    parse_game(&read_all(reader)?)
+}
+
+/// Writes a [Game] to output, in the JSON save format read by [read_game].
+///
+/// If output_file is None, then output will be written to stdout.
+pub fn write_game<P: AsRef<Path>>(game: &Game, output_file: Option<P>) -> Result<()> {
+    write_game_to(game, open_output(output_file)?)
+}
+
+/// Writes a [Game] to writer, the same as [write_game].
+pub fn write_game_to(game: &Game, mut writer: impl Write) -> Result<()> {
+    let saved = SavedGame {
+        grid: game.puzzle().to_string(),
+        elapsed_ms: u64::try_from(game.elapsed().as_millis()).unwrap_or(u64::MAX),
+        moves: game.moves().to_vec(),
     };
 
-    let mut writer: Box<dyn Write> = if let Some(output_file) = output_file {
+    writer.write_all((serde_json::to_string_pretty(&saved)? + "\n").as_bytes())?;
+
+    Ok(())
+}
+
+/// Reads an f-puzzles / SudokuPad puzzle into a [Puzzle] and its [Constraint]s.
+///
+/// If input_file is None, then input will be read from stdin.
+///
+/// The input can be plain f-puzzles JSON, the lz-string compressed base64 form of it, or a link
+/// that ends with either, e.g. `https://www.f-puzzles.com/?load=N4Ig...` or
+/// `https://sudokupad.app/fpuzzlesN4Ig...`.
+///
+/// Only 9x9 puzzles with standard regions are supported. Any constraint that has no matching
+/// [Constraint] will cause an error, rather than being silently dropped.
+pub fn read_fpuzzles<P: AsRef<Path>>(input_file: Option<P>) -> Result<(Puzzle, Vec<Constraint>)> {
+    read_fpuzzles_from(open_input(input_file)?)
+}
+
+/// Reads the entire content of reader into an f-puzzles puzzle, the same as [read_fpuzzles].
+pub fn read_fpuzzles_from(reader: impl Read) -> Result<(Puzzle, Vec<Constraint>)> {
+    fpuzzles::parse_fpuzzles(&read_all(reader)?)
+}
+
+/// Writes a variant puzzle to output, in the f-puzzles JSON format.
+///
+/// If output_file is None, then output will be written to stdout.
+///
+/// If compress is true, then the JSON will be compressed by lz-string into base64, which can be
+/// appended to `https://www.f-puzzles.com/?load=` or `https://sudokupad.app/fpuzzles`.
+///
+/// Greater-than signs have no f-puzzles equivalent, and will cause an error.
+pub fn write_fpuzzles<P: AsRef<Path>>(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    output_file: Option<P>,
+    compress: bool,
+) -> Result<()> {
+    write_fpuzzles_to(puzzle, constraints, open_output(output_file)?, compress)
+}
+
+/// Writes a variant puzzle to writer, the same as [write_fpuzzles].
+pub fn write_fpuzzles_to(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    mut writer: impl Write,
+    compress: bool,
+) -> Result<()> {
+    let mut output = fpuzzles::puzzle_to_fpuzzles(puzzle, constraints, compress)?;
+    output.push('\n');
+    writer.write_all(output.as_bytes())?;
+
+    Ok(())
+}
+
+/// Writes a [PuzzleRecord] to output, as an SVG image.
+///
+/// If output_file is None, then output will be written to stdout.
+///
+/// The givens are drawn in bold, and the digits of the solution (if any) in blue. Pencil marks
+/// (if any) are drawn in blank cells, and [Constraint]s are drawn as they are in f-puzzles. The
+/// output is deterministic.
+pub fn write_svg<P: AsRef<Path>>(record: &PuzzleRecord, output_file: Option<P>) -> Result<()> {
+    write_svg_to(record, open_output(output_file)?)
+}
+
+/// Writes a [PuzzleRecord] to writer, the same as [write_svg].
+pub fn write_svg_to(record: &PuzzleRecord, mut writer: impl Write) -> Result<()> {
+    writer.write_all(svg::record_to_svg(record).as_bytes())?;
+
+    Ok(())
+}
+
+/// Writes a puzzle to output, as a PNG image.
+///
+/// If output_file is None, then output will be written to stdout.
+///
+/// The digits in filled (if any) are drawn over the blanks of the puzzle, in a different colour
+/// from the givens, see [PngStyle].
+pub fn write_png<P: AsRef<Path>>(
+    puzzle: Grid,
+    filled: Option<Grid>,
+    style: &PngStyle,
+    output_file: Option<P>,
+) -> Result<()> {
+    write_png_to(puzzle, filled, style, open_output(output_file)?)
+}
+
+/// Writes a puzzle to writer, the same as [write_png].
+pub fn write_png_to(
+    puzzle: Grid,
+    filled: Option<Grid>,
+    style: &PngStyle,
+    mut writer: impl Write,
+) -> Result<()> {
+    writer.write_all(&png::grid_to_png(&puzzle, filled.as_ref(), style)?)?;
+
+    Ok(())
+}
+
+/// Writes puzzles to output, as a printable PDF booklet.
+///
+/// If output_file is None, then output will be written to stdout.
+///
+/// The puzzles are laid out per_page to a page, labelled with their number and the difficulty in
+/// their [Metadata] (if any). An answer key of their solutions (if any) follows at the end.
+///
+/// Records with [Constraint]s will cause an error, as they cannot be drawn.
+pub fn write_book<P: AsRef<Path>>(
+    records: &[PuzzleRecord],
+    per_page: usize,
+    output_file: Option<P>,
+) -> Result<()> {
+    write_book_to(records, per_page, open_output(output_file)?)
+}
+
+/// Writes puzzles to writer, the same as [write_book].
+pub fn write_book_to(
+    records: &[PuzzleRecord],
+    per_page: usize,
+    mut writer: impl Write,
+) -> Result<()> {
+    if records.iter().any(|record| !record.constraints.is_empty()) {
+        return Err(anyhow!(
+            "Unsupported: books cannot hold variant constraints."
+        ));
+    }
+
+    writer.write_all(pdf::records_to_pdf_book(records, per_page).as_bytes())?;
+
+    Ok(())
+}
+
+/// Reads input content into a list of [PuzzleRecord]s.
+///
+/// If input_file is None, then input will be read from stdin.
+///
+/// If format is None, then the format is guessed from the extension of input_file, or else from
+/// the content, see [FileFormat::detect]. Only [FileFormat::Sdm] can give more than one record.
+///
+/// [FileFormat::Grid] is parsed leniently, see [read_to_grid], and warnings about suspicious input
+/// are given along with the records. If strict is true, then any warning becomes an error.
+pub fn read_puzzles<P: AsRef<Path>>(
+    input_file: Option<P>,
+    format: Option<FileFormat>,
+    strict: bool,
+) -> Result<Parsed<Vec<PuzzleRecord>>> {
+    let extension_format = input_file.as_ref().and_then(FileFormat::from_extension);
+
+    read_puzzles_from(open_input(input_file)?, format.or(extension_format), strict)
+}
+
+/// Reads the entire content of reader into a list of [PuzzleRecord]s, the same as [read_puzzles].
+///
+/// If format is None, then the format is guessed from the content, see [FileFormat::detect].
+pub fn read_puzzles_from(
+    reader: impl Read,
+    format: Option<FileFormat>,
+    strict: bool,
+) -> Result<Parsed<Vec<PuzzleRecord>>> {
+    parse_puzzles(&read_all(reader)?, format, strict)
+}
+
+/// Writes a list of [PuzzleRecord]s to output.
+///
+/// If output_file is None, then output will be written to stdout.
+///
+/// Only [FileFormat::Sdm] can hold more than one record, except for [FileFormat::Grid], which
+/// writes the grids one after another, separated by an empty line. border and blank_char only
+/// apply to [FileFormat::Grid], see [write_grid] and [write_grid_with_edge_clues].
+///
+/// Formats that cannot keep the [Constraint]s of a record will cause an error, rather than
+/// silently dropping them. Metadata that cannot be kept is dropped.
+pub fn write_puzzles<P: AsRef<Path>>(
+    records: &[PuzzleRecord],
+    output_file: Option<P>,
+    format: FileFormat,
+    border: bool,
+    blank_char: &str,
+) -> Result<()> {
+    write_puzzles_to(
+        records,
+        open_output(output_file)?,
+        format,
+        border,
+        blank_char,
+    )
+}
+
+/// Writes a list of [PuzzleRecord]s to writer, the same as [write_puzzles].
+pub fn write_puzzles_to(
+    records: &[PuzzleRecord],
+    mut writer: impl Write,
+    format: FileFormat,
+    border: bool,
+    blank_char: &str,
+) -> Result<()> {
+    if records.len() > 1 && !format.holds_many() && format != FileFormat::Grid {
+        return Err(anyhow!("Unsupported: {format} can only hold one puzzle."));
+    }
+
+    if !format.holds_constraints() && records.iter().any(|record| !record.constraints.is_empty()) {
+        return Err(anyhow!(
+            "Unsupported: {format} cannot hold variant constraints."
+        ));
+    }
+
+    if format == FileFormat::Png {
+        let record = records.first().cloned().unwrap_or_default();
+        return write_png_to(record.puzzle, record.solution, &PngStyle::default(), writer);
+    }
+
+    let mut output = String::new();
+    for (index, record) in records.iter().enumerate() {
+        match format {
+            FileFormat::Grid => {
+                if index > 0 {
+                    output.push('\n');
+                }
+
+                output.push_str(&if border && !record.constraints.is_empty() {
+                    grid_to_edge_clue_string(record.puzzle, &record.constraints, blank_char)
+                } else {
+                    render_grid(&record.puzzle, GridStyle { border, blank_char })
+                });
+            }
+            FileFormat::Sdk => output.push_str(&sadman::record_to_sdk(record)),
+            FileFormat::Sdm => output.push_str(&sadman::record_to_sdm(record)),
+            FileFormat::Ss => output.push_str(&simple_sudoku::record_to_ss(record)),
+            FileFormat::Variant => {
+                output.push_str(&variant_to_string(record.puzzle, &record.constraints)?)
+            }
+            FileFormat::Fpuzzles => {
+                output.push_str(&fpuzzles::puzzle_to_fpuzzles(
+                    record.puzzle,
+                    &record.constraints,
+                    true,
+                )?);
+                output.push('\n');
+            }
+            FileFormat::Svg => output.push_str(&svg::record_to_svg(record)),
+            FileFormat::Png => unreachable!("written above"),
+        }
+    }
+    writer.write_all(output.as_bytes())?;
+
+    Ok(())
+}
+
+/// Opens input_file for reading.
+///
+/// If input_file is None, then input will be read from stdin.
+fn open_input<P: AsRef<Path>>(input_file: Option<P>) -> Result<Box<dyn Read>> {
+    Ok(if let Some(input_file) = input_file {
+        Box::new(File::open(input_file)?)
+    } else {
+        Box::new(BufReader::new(io::stdin().lock()))
+    })
+}
+
+/// Opens output_file for writing, replacing its content.
+///
+/// If output_file is None, then output will be written to stdout.
+fn open_output<P: AsRef<Path>>(output_file: Option<P>) -> Result<Box<dyn Write>> {
+    Ok(if let Some(output_file) = output_file {
         Box::new(File::create(output_file)?)
     } else {
         Box::new(io::stdout().lock())
+    })
+}
+
+/// Reads the entire content of reader into a String.
+fn read_all(mut reader: impl Read) -> Result<String> {
+    let mut buffer = String::new();
+    reader.read_to_string(&mut buffer)?;
+
+    Ok(buffer)
+}
+
+/// Parses content into a list of [PuzzleRecord]s, see [read_puzzles_from].
+fn parse_puzzles(
+    content: &str,
+    format: Option<FileFormat>,
+    strict: bool,
+) -> Result<Parsed<Vec<PuzzleRecord>>> {
+    let format = format.unwrap_or_else(|| FileFormat::detect(content));
+
+    let with_constraints = |(puzzle, constraints)| PuzzleRecord {
+        puzzle,
+        constraints,
+        ..Default::default()
     };
-    writer.write_all(output.as_bytes())?;
 
-    Ok(())
+    let mut warnings = Vec::new();
+
+    let records = match format {
+        FileFormat::Grid => {
+            let parsed = lenient::parse_grid_text(content, strict)?;
+            warnings = parsed.warnings;
+
+            vec![parsed.value]
+        }
+        FileFormat::Sdk => vec![sadman::parse_sdk(content)?],
+        FileFormat::Sdm => sadman::parse_sdm(content)?,
+        FileFormat::Ss => vec![simple_sudoku::parse_ss(content)?],
+        FileFormat::Variant => vec![with_constraints(parse_variant(content)?)],
+        FileFormat::Fpuzzles => vec![with_constraints(fpuzzles::parse_fpuzzles(content)?)],
+        FileFormat::Svg | FileFormat::Png => {
+            return Err(anyhow!("Unsupported: {format} cannot be read."));
+        }
+    };
+
+    Ok(Parsed {
+        value: records,
+        warnings,
+    })
+}
+
+/// Parses a saved game in the JSON save format, see [read_game].
+fn parse_game(content: &str) -> Result<Game> {
+    let saved: SavedGame = serde_json::from_str(content)
+        .map_err(|error| anyhow!("Invalid input: bad saved game: {error}."))?;
+
+    let mut game = Game::replay(Grid::from_str(&saved.grid)?, &saved.moves)?;
+    game.set_elapsed(Duration::from_millis(saved.elapsed_ms));
+
+    Ok(game)
+}
+
+/// Parses a variant puzzle described in JSON, see [read_variant_puzzle].
+fn parse_variant(buffer: &str) -> Result<(Puzzle, Vec<Constraint>)> {
+    let description: VariantDescription = serde_json::from_str(buffer)
+        .map_err(|error| anyhow!("Invalid input: bad variant description: {error}."))?;
+
+    let puzzle = Grid::from_str(&description.grid)?;
+
+    if let Some(index) = description
+        .constraints
+        .iter()
+        .position(|constraint| !constraint.is_well_formed())
+    {
+        return Err(anyhow!(
+            "Invalid input: clue {} ({}) is malformed.",
+            index + 1,
+            description.constraints[index].name()
+        ));
+    }
+
+    Ok((puzzle, description.constraints))
+}
+
+/// Converts a variant puzzle to the JSON description format, see [write_variant_puzzle].
+fn variant_to_string(puzzle: Puzzle, constraints: &[Constraint]) -> Result<String> {
+    let description = VariantDescription {
+        grid: puzzle.to_string(),
+        constraints: constraints.to_vec(),
+    };
+
+    Ok(serde_json::to_string_pretty(&description)? + "\n")
 }
 
 /// Converts a [Grid] to a String for printing.
@@ -150,6 +1006,7 @@ pub fn write_grid<P: AsRef<Path>>(
 /// Output will end with a newline char.
 fn grid_to_string(grid: Grid, blank_char: &str) -> String {
     let mut output = grid
+        .as_array()
         .map(|row| String::from_utf8_lossy(&row.map(|byte| byte + b'0')).replace("0", blank_char))
         .join("\n");
     output.push('\n');
@@ -163,15 +1020,15 @@ fn grid_to_string(grid: Grid, blank_char: &str) -> String {
 fn grid_to_border_string(grid: Grid, blank_char: &str) -> String {
     format!(
         "╔═══╤═══╤═══╗\n{}╟───┼───┼───╢\n{}╟───┼───┼───╢\n{}╚═══╧═══╧═══╝\n",
-        &grid[..3]
+        &grid.as_array()[..3]
             .iter()
             .map(|row| grid_row_to_border_string(*row))
             .collect::<String>(),
-        &grid[3..6]
+        &grid.as_array()[3..6]
             .iter()
             .map(|row| grid_row_to_border_string(*row))
             .collect::<String>(),
-        &grid[6..]
+        &grid.as_array()[6..]
             .iter()
             .map(|row| grid_row_to_border_string(*row))
             .collect::<String>(),
@@ -185,3 +1042,260 @@ fn grid_row_to_border_string(row: [u8; 9]) -> String {
         row[0], row[1], row[2], row[3], row[4], row[5], row[6], row[7], row[8],
     )
 }
+
+/// Converts a [Grid] with edge clues to a String for printing.
+///
+/// Output will end with a newline char.
+fn grid_to_edge_clue_string(grid: Grid, constraints: &[Constraint], blank_char: &str) -> String {
+    // Row or col index of cell i, leaving a gap on both sides of every box border for edge clues,
+    // so that a clue between two boxes never hides the border.
+    let at = |i: usize| 2 + i * 2 + i / 3 * 2;
+    let mut lines = (0..25)
+        .map(|y| {
+            let (left, fill, cross, right) = match y {
+                0 => ('╔', '═', '╤', '╗'),
+                8 | 16 => ('╟', '─', '┼', '╢'),
+                24 => ('╚', '═', '╧', '╝'),
+                _ => ('║', ' ', '│', '║'),
+            };
+            (0..25)
+                .map(|x| match x {
+                    0 => left,
+                    8 | 16 => cross,
+                    24 => right,
+                    _ => fill,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    for (row, digits) in grid.rows().enumerate() {
+        for (col, digit) in digits.into_iter().enumerate() {
+            lines[at(row)][at(col)] = char::from(b'0' + digit);
+        }
+    }
+
+    for constraint in constraints {
+        let Some([(row_a, col_a), (row_b, col_b)]) = constraint.edge() else {
+            continue;
+        };
+        if !constraint.is_well_formed() {
+            continue;
+        }
+
+        let horizontal = row_a == row_b;
+        let marker = match constraint {
+            Constraint::WhiteDot { .. } => '○',
+            Constraint::BlackDot { .. } => '●',
+            Constraint::X { .. } => 'X',
+            Constraint::V { .. } => 'V',
+            Constraint::GreaterThan { .. } => match (horizontal, col_a < col_b, row_a < row_b) {
+                (true, true, _) => '>',
+                (true, false, _) => '<',
+                (false, _, true) => '∨',
+                (false, _, false) => '∧',
+            },
+            _ => continue,
+        };
+
+        // The clue goes in the gap right of or below the first of the two cells.
+        if horizontal {
+            lines[at(row_a)][at(col_a.min(col_b)) + 1] = marker;
+        } else {
+            lines[at(row_a.min(row_b)) + 1][at(col_a)] = marker;
+        }
+    }
+
+    let mut output = lines
+        .into_iter()
+        .map(|line| line.into_iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace("0", blank_char);
+    output.push('\n');
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_detect() {
+        let grid = "53..7....\n6..195...\n.98....6.\n8...6...3\n4..8.3..1\n7...2...6\n.6....28.\n...419..5\n....8..79\n";
+        assert_eq!(FileFormat::detect(grid), FileFormat::Grid);
+        assert_eq!(
+            FileFormat::detect(&grid_to_border_string(
+                lenient::parse_grid_text(grid, false).unwrap().value.puzzle,
+                " "
+            )),
+            FileFormat::Grid
+        );
+
+        assert_eq!(FileFormat::detect("[Puzzle]\n#AJane\n"), FileFormat::Sdk);
+        assert_eq!(FileFormat::detect(&"0".repeat(81)), FileFormat::Sdm);
+        let line = format!("53__7____6__195___{}", "_".repeat(63));
+        assert_eq!(FileFormat::detect(&line), FileFormat::Grid);
+        let parsed = parse_puzzles(&line, None, true).unwrap();
+        assert_eq!(parsed.value[0].puzzle[1][3], 1);
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(
+            FileFormat::detect("53.|.7.|...\n-----------\n"),
+            FileFormat::Ss
+        );
+        assert_eq!(
+            FileFormat::detect(r#"{"grid": "", "constraints": []}"#),
+            FileFormat::Variant
+        );
+        assert_eq!(FileFormat::detect(r#"{"size": 9}"#), FileFormat::Fpuzzles);
+        assert_eq!(
+            FileFormat::detect("https://sudokupad.app/fpuzzlesN4Ig"),
+            FileFormat::Fpuzzles
+        );
+
+        assert_eq!(FileFormat::from_extension("a/b.SDK"), Some(FileFormat::Sdk));
+        assert_eq!(FileFormat::from_extension("b.txt"), None);
+    }
+
+    #[test]
+    fn check_render_grid() {
+        let grid = parse_grid(&format!("53..7....{}", ".".repeat(72))).unwrap();
+        assert_eq!(grid[0], [5, 3, 0, 0, 7, 0, 0, 0, 0]);
+        assert!(parse_grid("123").is_err());
+
+        let styles = [
+            GridStyle::default(),
+            GridStyle {
+                border: false,
+                blank_char: ".",
+            },
+        ];
+        for style in styles {
+            let text = render_grid(&grid, style);
+            assert!(text.ends_with('\n'));
+            assert_eq!(parse_grid(&text).unwrap(), grid);
+            assert_eq!(read_grid(text.as_bytes()).unwrap(), grid);
+
+            let mut output = Vec::new();
+            write_grid_to(&grid, &mut output, style).unwrap();
+            assert_eq!(output, text.into_bytes());
+        }
+        assert_eq!(
+            render_grid(&grid, GridStyle::default()).lines().nth(1),
+            Some("║53 │ 7 │   ║")
+        );
+    }
+
+    #[test]
+    fn check_read_and_write_buffers() {
+        let puzzle = parse_grid(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let record = PuzzleRecord {
+            puzzle,
+            ..Default::default()
+        };
+
+        let formats = [
+            FileFormat::Grid,
+            FileFormat::Sdk,
+            FileFormat::Sdm,
+            FileFormat::Ss,
+            FileFormat::Variant,
+            FileFormat::Fpuzzles,
+        ];
+        for format in formats {
+            let mut output = Vec::new();
+            write_puzzles_to(
+                std::slice::from_ref(&record),
+                &mut output,
+                format,
+                true,
+                " ",
+            )
+            .unwrap();
+            let read = read_puzzles_from(output.as_slice(), Some(format), true).unwrap();
+            assert_eq!(read.value[0].puzzle, puzzle, "{format}");
+        }
+
+        let constraints = vec![Constraint::WhiteDot {
+            cells: [(0, 2), (0, 3)],
+        }];
+        let mut output = Vec::new();
+        write_variant_puzzle_to(puzzle, &constraints, &mut output).unwrap();
+        assert_eq!(
+            read_variant_puzzle_from(output.as_slice()).unwrap(),
+            (puzzle, constraints.clone())
+        );
+        let mut output = Vec::new();
+        write_fpuzzles_to(puzzle, &constraints, &mut output, false).unwrap();
+        assert_eq!(
+            read_fpuzzles_from(output.as_slice()).unwrap(),
+            (puzzle, constraints.clone())
+        );
+
+        let mut game = Game::new(puzzle).unwrap();
+        game.play(Move::Enter {
+            pos: (0, 2),
+            digit: 4,
+        })
+        .unwrap();
+        let mut output = Vec::new();
+        write_game_to(&game, &mut output).unwrap();
+        let read = read_game_from(output.as_slice()).unwrap();
+        assert_eq!(read.board(), game.board());
+        assert_eq!(read.moves(), game.moves());
+
+        let mut output = Vec::new();
+        write_grid_with_edge_clues_to(puzzle, &constraints, &mut output, ".").unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains('○'));
+        // Clues between two boxes sit beside the box borders rather than on them.
+        assert!(output.lines().all(|line| line.chars().count() == 25));
+        assert!(
+            output
+                .lines()
+                .all(|line| line.chars().nth(8) == line.chars().nth(16))
+        );
+        let mut output = Vec::new();
+        write_grid_colored_to(puzzle, None, &[], &mut output, false, ".").unwrap();
+        assert!(output.starts_with(b"\x1b["));
+        let text = render_grid(&puzzle, GridStyle::default()).replacen('5', "s", 1);
+        let read = read_grid_strict(text.as_bytes(), false).unwrap();
+        assert_eq!(read.warnings.len(), 1);
+        assert!(read_grid_strict(text.as_bytes(), true).is_err());
+
+        let mut output = Vec::new();
+        write_svg_to(&record, &mut output).unwrap();
+        assert!(output.starts_with(b"<svg"));
+        let mut output = Vec::new();
+        write_png_to(puzzle, None, &PngStyle::default(), &mut output).unwrap();
+        assert!(output.starts_with(b"\x89PNG"));
+        let mut output = Vec::new();
+        write_book_to(&[record], 6, &mut output).unwrap();
+        assert!(output.starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn check_read_bad_buffers() {
+        let sdm = format!("{}\n{}\n", "0".repeat(81), "1".repeat(81));
+        let read = read_puzzles_from(sdm.as_bytes(), None, true).unwrap();
+        assert_eq!(read.value.len(), 2);
+        assert!(read_puzzles_from(sdm.as_bytes(), Some(FileFormat::Ss), true).is_err());
+        assert!(read_puzzles_from(&b""[..], Some(FileFormat::Png), false).is_err());
+        assert!(read_puzzles_from(&b"\xff\xfe"[..], None, false).is_err());
+
+        assert!(read_game_from(&br#"{"grid": ""}"#[..]).is_err());
+        assert!(read_game_from(&b"{"[..]).is_err());
+
+        // Grids in JSON take only digits, `.` and `0`, rather than taking any other char as blank.
+        let grid = format!("l{}", ".".repeat(80));
+        let game = format!(r#"{{"grid": "{grid}", "moves": [], "elapsed_ms": 0}}"#);
+        let error = read_game_from(game.as_bytes()).unwrap_err();
+        assert!(error.to_string().contains("'l'"), "{error}");
+        let variant = format!(r#"{{"grid": "{grid}", "constraints": []}}"#);
+        assert!(read_variant_puzzle_from(variant.as_bytes()).is_err());
+    }
+}
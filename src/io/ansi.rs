@@ -0,0 +1,122 @@
+//! Coloured output for terminals, using ANSI escape codes.
+
+use crate::sudoku::{Grid, GridPos, has_conflict};
+
+const RESET: &str = "\x1b[0m";
+const GIVEN: &str = "\x1b[1m";
+const FILLED: &str = "\x1b[36m";
+const CONFLICT: &str = "\x1b[1;31m";
+const HIGHLIGHT: &str = "\x1b[43;30m";
+
+/// Converts a puzzle to a String for printing in a terminal, with the digits in filled (if any)
+/// drawn over its blanks.
+///
+/// Givens are bold, filled in digits are cyan, and digits that are repeated in their row, col or
+/// square are red. The cells in highlights have a yellow background.
+///
+/// The layout is the same as [write_grid](crate::io::write_grid), where any 0 value is replaced
+/// by blank_char. Output will end with a newline char.
+pub fn grid_to_ansi(
+    puzzle: &Grid,
+    filled: Option<&Grid>,
+    highlights: &[GridPos],
+    border: bool,
+    blank_char: &str,
+) -> String {
+    let mut grid = *puzzle;
+    if let Some(filled) = filled {
+        for pos in (0..81).map(|index| (index / 9, index % 9)) {
+            if grid[pos] == 0 {
+                grid.set(pos, filled[pos]);
+            }
+        }
+    }
+
+    let cell = |row: usize, col: usize| {
+        let digit = grid[row][col];
+        let text = if digit == 0 {
+            blank_char.to_string()
+        } else {
+            digit.to_string()
+        };
+
+        let mut style = String::new();
+        if highlights.contains(&(row, col)) {
+            style.push_str(HIGHLIGHT);
+        }
+        if has_conflict(&grid, (row, col)) {
+            style.push_str(CONFLICT);
+        } else if puzzle[row][col] != 0 {
+            style.push_str(GIVEN);
+        } else if digit != 0 {
+            style.push_str(FILLED);
+        }
+
+        if style.is_empty() {
+            text
+        } else {
+            format!("{style}{text}{RESET}")
+        }
+    };
+
+    let mut output = String::new();
+    if border {
+        output.push_str("╔═══╤═══╤═══╗\n");
+    }
+
+    for row in 0..9 {
+        if border && (row == 3 || row == 6) {
+            output.push_str("╟───┼───┼───╢\n");
+        }
+
+        for col in 0..9 {
+            if border {
+                output.push_str(match col {
+                    0 => "║",
+                    3 | 6 => "│",
+                    _ => "",
+                });
+            }
+            output.push_str(&cell(row, col));
+        }
+
+        if border {
+            output.push('║');
+        }
+        output.push('\n');
+    }
+
+    if border {
+        output.push_str("╚═══╧═══╧═══╝\n");
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_ansi() {
+        let mut puzzle = Grid::EMPTY;
+        puzzle.set((0, 0), 5);
+        let mut filled = Grid::EMPTY;
+        filled.set((0, 1), 3);
+        filled.set((0, 2), 3);
+
+        let output = grid_to_ansi(&puzzle, Some(&filled), &[(0, 3)], false, ".");
+        assert_eq!(
+            output.lines().next().unwrap(),
+            "\x1b[1m5\x1b[0m\x1b[1;31m3\x1b[0m\x1b[1;31m3\x1b[0m\x1b[43;30m.\x1b[0m....."
+        );
+        assert_eq!(output.lines().nth(1).unwrap(), ".........");
+
+        let output = grid_to_ansi(&puzzle, None, &[], true, " ");
+        assert_eq!(output.lines().count(), 13);
+        assert_eq!(
+            output.lines().nth(1).unwrap(),
+            "║\x1b[1m5\x1b[0m  │   │   ║"
+        );
+    }
+}
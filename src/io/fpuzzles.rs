@@ -0,0 +1,521 @@
+//! The f-puzzles JSON format, which is also accepted by SudokuPad.
+//!
+//! Puzzles are shared either as plain JSON, or as JSON compressed by lz-string into base64, often
+//! as part of a link (e.g. `https://www.f-puzzles.com/?load=N4Ig...` or
+//! `https://sudokupad.app/fpuzzlesN4Ig...`).
+
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value, json};
+
+use crate::{
+    io::lz_string::{compress_to_base64, decompress_from_base64},
+    sudoku::{Grid, GridPos, Puzzle},
+    variant::{Constraint, Line},
+};
+
+/// Top-level keys that carry no rules (metadata and cosmetic decorations), and can be safely
+/// ignored.
+const IGNORED_KEYS: [&str; 14] = [
+    "size",
+    "grid",
+    "title",
+    "author",
+    "ruleset",
+    "solution",
+    "highlightConflicts",
+    "disabledlogic",
+    "truecandidatesoptions",
+    "text",
+    "line",
+    "rectangle",
+    "circle",
+    "cage",
+];
+
+/// Top-level keys for constraints that map to a [Constraint].
+const SUPPORTED_KEYS: [&str; 6] = [
+    "thermometer",
+    "arrow",
+    "sandwichsum",
+    "difference",
+    "ratio",
+    "xv",
+];
+
+/// Parses an f-puzzles puzzle into a [Puzzle] and its [Constraint]s.
+///
+/// The input can be plain JSON, JSON compressed by lz-string into base64, or a link that ends with
+/// either.
+pub fn parse_fpuzzles(input: &str) -> Result<(Puzzle, Vec<Constraint>)> {
+    let json = decode(input.trim())?;
+
+    let root: Value = serde_json::from_str(&json)
+        .map_err(|error| anyhow!("Invalid input: bad f-puzzles JSON: {error}."))?;
+    let root = root
+        .as_object()
+        .ok_or(anyhow!("Invalid input: f-puzzles JSON must be an object."))?;
+
+    if root.get("size").and_then(Value::as_u64) != Some(9) {
+        return Err(anyhow!("Unsupported: only 9x9 puzzles are supported."));
+    }
+
+    let unsupported = root
+        .iter()
+        .filter(|(key, value)| {
+            !IGNORED_KEYS.contains(&key.as_str())
+                && !SUPPORTED_KEYS.contains(&key.as_str())
+                && !is_empty(value)
+        })
+        .map(|(key, _)| key.as_str())
+        .collect::<Vec<_>>();
+    if !unsupported.is_empty() {
+        return Err(anyhow!(
+            "Unsupported: f-puzzles features {} are not supported.",
+            unsupported.join(", ")
+        ));
+    }
+
+    let puzzle = parse_grid(root)?;
+
+    // Keys are visited in sorted order, so constraints come out grouped by type.
+    let mut constraints = Vec::new();
+    for (key, value) in root {
+        if SUPPORTED_KEYS.contains(&key.as_str()) {
+            constraints.extend(parse_constraints(key, value)?);
+        }
+    }
+
+    Ok((puzzle, constraints))
+}
+
+/// Converts a [Puzzle] and its [Constraint]s to f-puzzles JSON.
+///
+/// If compress is true, then the JSON will be compressed by lz-string into base64, which is what
+/// f-puzzles and SudokuPad links expect.
+pub fn puzzle_to_fpuzzles(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    compress: bool,
+) -> Result<String> {
+    let grid = puzzle
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|digit| {
+                    if *digit == 0 {
+                        json!({})
+                    } else {
+                        json!({ "value": digit, "given": true })
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut root = Map::new();
+    root.insert("size".into(), json!(9));
+    root.insert("grid".into(), json!(grid));
+
+    for (index, constraint) in constraints.iter().enumerate() {
+        if !constraint.is_well_formed() {
+            return Err(anyhow!("Invalid input: clue {} is malformed.", index + 1));
+        }
+
+        let (key, entry) = match constraint {
+            Constraint::Thermometer { cells } => {
+                ("thermometer", json!({ "lines": [cell_names(cells)] }))
+            }
+            Constraint::Arrow { circle, arrow } => (
+                "arrow",
+                json!({
+                    "lines": [cell_names(&[&circle[..1], arrow.as_slice()].concat())],
+                    "cells": cell_names(circle),
+                }),
+            ),
+            Constraint::Sandwich { line, sum } => {
+                let cell = match line {
+                    Line::Row(row) => format!("R{}C0", row + 1),
+                    Line::Col(col) => format!("R0C{}", col + 1),
+                };
+                (
+                    "sandwichsum",
+                    json!({ "cell": cell, "value": sum.to_string() }),
+                )
+            }
+            Constraint::WhiteDot { cells } => ("difference", json!({ "cells": cell_names(cells) })),
+            Constraint::BlackDot { cells } => ("ratio", json!({ "cells": cell_names(cells) })),
+            Constraint::X { cells } => ("xv", json!({ "cells": cell_names(cells), "value": "X" })),
+            Constraint::V { cells } => ("xv", json!({ "cells": cell_names(cells), "value": "V" })),
+            Constraint::GreaterThan { .. } => {
+                return Err(anyhow!(
+                    "Unsupported: clue {} ({}) cannot be written in the f-puzzles format.",
+                    index + 1,
+                    constraint.name()
+                ));
+            }
+        };
+
+        if let Value::Array(entries) = root.entry(key).or_insert(json!([])) {
+            entries.push(entry);
+        }
+    }
+
+    let output = serde_json::to_string(&Value::Object(root))?;
+
+    Ok(if compress {
+        compress_to_base64(&output)
+    } else {
+        output
+    })
+}
+
+/// Gets the JSON out of plain JSON, base64 or a link.
+fn decode(input: &str) -> Result<String> {
+    if input.starts_with('{') {
+        return Ok(input.to_string());
+    }
+
+    // Take whatever follows the last known marker in a link.
+    let data = ["?load=", "&load=", "fpuzzles"]
+        .iter()
+        .filter_map(|marker| {
+            input
+                .rfind(marker)
+                .map(|index| &input[index + marker.len()..])
+        })
+        .min_by_key(|data| data.len())
+        .unwrap_or(input);
+
+    // Links may have mangled the base64 chars.
+    let data = data
+        .split(['&', '#'])
+        .next()
+        .unwrap_or_default()
+        .replace("%2B", "+")
+        .replace("%2F", "/")
+        .replace("%3D", "=")
+        .replace(' ', "+");
+
+    decompress_from_base64(&data)
+}
+
+/// Verifies whether a JSON value carries no information, i.e. an empty list of constraints or a
+/// disabled global constraint.
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => true,
+        Value::Array(values) => values.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+/// Parses the grid of cells, keeping only the givens.
+fn parse_grid(root: &Map<String, Value>) -> Result<Puzzle> {
+    let rows = root
+        .get("grid")
+        .and_then(Value::as_array)
+        .filter(|rows| rows.len() == 9)
+        .ok_or(anyhow!("Invalid input: f-puzzles grid must have 9 rows."))?;
+
+    let mut puzzle = Grid::EMPTY;
+    for (row, cells) in rows.iter().enumerate() {
+        let cells = cells
+            .as_array()
+            .filter(|cells| cells.len() == 9)
+            .ok_or(anyhow!("Invalid input: f-puzzles grid must have 9 cols."))?;
+
+        for (col, cell) in cells.iter().enumerate() {
+            // Regions default to the standard squares, so only irregular regions need a value.
+            if let Some(region) = cell.get("region")
+                && region.as_u64() != Some((row / 3 * 3 + col / 3) as u64)
+            {
+                return Err(anyhow!("Unsupported: irregular regions are not supported."));
+            }
+
+            if cell.get("given").and_then(Value::as_bool) == Some(true)
+                && let Some(digit) = cell.get("value").and_then(Value::as_u64)
+            {
+                if !(1..=9).contains(&digit) {
+                    return Err(anyhow!(
+                        "Invalid input: given at R{}C{} is not a digit.",
+                        row + 1,
+                        col + 1
+                    ));
+                }
+
+                puzzle.set((row, col), digit as u8);
+            }
+        }
+    }
+
+    Ok(puzzle)
+}
+
+/// Parses the list of constraints under one of the [SUPPORTED_KEYS].
+fn parse_constraints(key: &str, value: &Value) -> Result<Vec<Constraint>> {
+    let entries = match value {
+        Value::Null => return Ok(Vec::new()),
+        Value::Array(entries) => entries,
+        _ => return Err(anyhow!("Invalid input: f-puzzles {key} must be a list.")),
+    };
+
+    let mut constraints = Vec::new();
+    for entry in entries {
+        match key {
+            "thermometer" => {
+                for line in lines(entry, key)? {
+                    constraints.push(Constraint::Thermometer { cells: line });
+                }
+            }
+            "arrow" => {
+                let circle = cells(entry, key)?;
+                for line in lines(entry, key)? {
+                    // Lines start from inside the circle.
+                    let arrow = line
+                        .into_iter()
+                        .filter(|cell| !circle.contains(cell))
+                        .collect();
+                    constraints.push(Constraint::Arrow {
+                        circle: circle.clone(),
+                        arrow,
+                    });
+                }
+            }
+            "sandwichsum" => {
+                let (row, col) = entry
+                    .get("cell")
+                    .and_then(Value::as_str)
+                    .and_then(parse_cell_name)
+                    .ok_or(anyhow!("Invalid input: f-puzzles {key} has a bad cell."))?;
+                let line = match (row, col) {
+                    (0, 1..=9) => Line::Col(col - 1),
+                    (1..=9, 0) => Line::Row(row - 1),
+                    _ => return Err(anyhow!("Invalid input: f-puzzles {key} has a bad cell.")),
+                };
+                constraints.push(Constraint::Sandwich {
+                    line,
+                    sum: number(entry, key)?,
+                });
+            }
+            "difference" | "ratio" | "xv" => {
+                let cells: [GridPos; 2] = cells(entry, key)?
+                    .try_into()
+                    .map_err(|_| anyhow!("Invalid input: f-puzzles {key} must have 2 cells."))?;
+
+                let constraint = match (key, entry.get("value").and_then(Value::as_str)) {
+                    ("difference", None) => Constraint::WhiteDot { cells },
+                    ("ratio", None) => Constraint::BlackDot { cells },
+                    ("xv", Some("X" | "x")) => Constraint::X { cells },
+                    ("xv", Some("V" | "v")) => Constraint::V { cells },
+                    _ => {
+                        return Err(anyhow!(
+                            "Unsupported: f-puzzles {key} with a value is not supported."
+                        ));
+                    }
+                };
+                constraints.push(constraint);
+            }
+            _ => unreachable!("only called for supported keys"),
+        }
+    }
+
+    if let Some(constraint) = constraints.iter().find(|c| !c.is_well_formed()) {
+        return Err(anyhow!(
+            "Invalid input: f-puzzles has a malformed {}.",
+            constraint.name()
+        ));
+    }
+
+    Ok(constraints)
+}
+
+/// Gets the "lines" of an entry, as lists of (row, col).
+fn lines(entry: &Value, key: &str) -> Result<Vec<Vec<GridPos>>> {
+    entry
+        .get("lines")
+        .and_then(Value::as_array)
+        .ok_or(anyhow!("Invalid input: f-puzzles {key} has no lines."))?
+        .iter()
+        .map(|line| cell_list(line, key))
+        .collect()
+}
+
+/// Gets the "cells" of an entry, as a list of (row, col).
+fn cells(entry: &Value, key: &str) -> Result<Vec<GridPos>> {
+    cell_list(
+        entry
+            .get("cells")
+            .ok_or(anyhow!("Invalid input: f-puzzles {key} has no cells."))?,
+        key,
+    )
+}
+
+/// Converts a list of cell names to a list of (row, col) inside the grid.
+fn cell_list(value: &Value, key: &str) -> Result<Vec<GridPos>> {
+    value
+        .as_array()
+        .ok_or(anyhow!("Invalid input: f-puzzles {key} has bad cells."))?
+        .iter()
+        .map(|cell| {
+            cell.as_str()
+                .and_then(parse_cell_name)
+                .filter(|(row, col)| (1..=9).contains(row) && (1..=9).contains(col))
+                .map(|(row, col)| (row - 1, col - 1))
+                .ok_or(anyhow!("Invalid input: f-puzzles {key} has a bad cell."))
+        })
+        .collect()
+}
+
+/// Gets the "value" of an entry, which may either be a number or a string.
+fn number(entry: &Value, key: &str) -> Result<u8> {
+    let value = entry.get("value");
+
+    value
+        .and_then(Value::as_u64)
+        .or_else(|| value.and_then(Value::as_str)?.trim().parse().ok())
+        .and_then(|value| u8::try_from(value).ok())
+        .ok_or(anyhow!("Invalid input: f-puzzles {key} has a bad value."))
+}
+
+/// Parses a cell name like "R1C2" into (1, 2), without checking the range.
+fn parse_cell_name(name: &str) -> Option<(usize, usize)> {
+    let (row, col) = name.strip_prefix('R')?.split_once('C')?;
+
+    Some((row.parse().ok()?, col.parse().ok()?))
+}
+
+/// Converts a list of (row, col) to a list of cell names like "R1C2".
+fn cell_names(cells: &[GridPos]) -> Vec<String> {
+    cells
+        .iter()
+        .map(|(row, col)| format!("R{}C{}", row + 1, col + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FPUZZLES_JSON: &str = r#"{
+        "size": 9,
+        "title": "Test",
+        "author": "Test",
+        "grid": [
+            [{"value": 5, "given": true}, {"value": 3, "given": true}, {}, {}, {"value": 7, "given": true}, {}, {}, {}, {}],
+            [{"value": 6, "given": true}, {}, {}, {}, {}, {}, {}, {}, {}],
+            [{}, {}, {}, {}, {}, {}, {}, {}, {"value": 4}],
+            [{}, {}, {}, {}, {}, {}, {}, {}, {}],
+            [{}, {}, {}, {}, {"region": 4}, {}, {}, {}, {}],
+            [{}, {}, {}, {}, {}, {}, {}, {}, {}],
+            [{}, {}, {}, {}, {}, {}, {}, {}, {}],
+            [{}, {}, {}, {}, {}, {}, {}, {}, {}],
+            [{}, {}, {}, {}, {}, {}, {}, {}, {}]
+        ],
+        "thermometer": [{"lines": [["R1C3", "R1C4", "R2C5"]]}],
+        "arrow": [{"lines": [["R1C7", "R1C4", "R1C2"]], "cells": ["R1C7"]}],
+        "sandwichsum": [{"cell": "R0C1", "value": "19"}, {"cell": "R1C0", "value": 0}],
+        "difference": [{"cells": ["R1C2", "R1C3"]}],
+        "ratio": [{"cells": ["R4C1", "R5C1"]}],
+        "xv": [{"cells": ["R1C7", "R1C8"], "value": "X"}],
+        "killercage": []
+    }"#;
+
+    #[test]
+    fn check_parse() {
+        let (puzzle, constraints) = parse_fpuzzles(FPUZZLES_JSON).unwrap();
+
+        assert_eq!(puzzle[0], [5, 3, 0, 0, 7, 0, 0, 0, 0]);
+        assert_eq!(puzzle[1], [6, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(puzzle[2], [0; 9]);
+
+        assert_eq!(
+            constraints,
+            vec![
+                Constraint::Arrow {
+                    circle: vec![(0, 6)],
+                    arrow: vec![(0, 3), (0, 1)]
+                },
+                Constraint::WhiteDot {
+                    cells: [(0, 1), (0, 2)]
+                },
+                Constraint::BlackDot {
+                    cells: [(3, 0), (4, 0)]
+                },
+                Constraint::Sandwich {
+                    line: Line::Col(0),
+                    sum: 19
+                },
+                Constraint::Sandwich {
+                    line: Line::Row(0),
+                    sum: 0
+                },
+                Constraint::Thermometer {
+                    cells: vec![(0, 2), (0, 3), (1, 4)]
+                },
+                Constraint::X {
+                    cells: [(0, 6), (0, 7)]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn check_round_trip() {
+        let (puzzle, constraints) = parse_fpuzzles(FPUZZLES_JSON).unwrap();
+
+        for compress in [false, true] {
+            let output = puzzle_to_fpuzzles(puzzle, &constraints, compress).unwrap();
+            assert_eq!(
+                parse_fpuzzles(&output).unwrap(),
+                (puzzle, constraints.clone())
+            );
+        }
+
+        let link = format!(
+            "https://sudokupad.app/fpuzzles{}",
+            puzzle_to_fpuzzles(puzzle, &constraints, true).unwrap()
+        );
+        assert_eq!(parse_fpuzzles(&link).unwrap(), (puzzle, constraints));
+    }
+
+    #[test]
+    fn check_unsupported() {
+        let killer = FPUZZLES_JSON.replace(
+            r#""killercage": []"#,
+            r#""killercage": [{"cells": ["R1C1"], "value": "5"}]"#,
+        );
+        assert!(
+            parse_fpuzzles(&killer)
+                .unwrap_err()
+                .to_string()
+                .contains("killercage")
+        );
+
+        // A disabled global constraint, as SudokuPad exports, is fine, but an enabled one is not.
+        let antiknight = |enabled| {
+            FPUZZLES_JSON.replace(
+                r#""killercage": []"#,
+                &format!(r#""killercage": [], "antiknight": {enabled}"#),
+            )
+        };
+        assert!(parse_fpuzzles(&antiknight(false)).is_ok());
+        assert!(
+            parse_fpuzzles(&antiknight(true))
+                .unwrap_err()
+                .to_string()
+                .contains("antiknight")
+        );
+
+        let irregular = FPUZZLES_JSON.replace(r#"{"region": 4}"#, r#"{"region": 3}"#);
+        assert!(parse_fpuzzles(&irregular).is_err());
+
+        let six = FPUZZLES_JSON.replace(r#""size": 9"#, r#""size": 6"#);
+        assert!(parse_fpuzzles(&six).is_err());
+
+        let greater_than = [Constraint::GreaterThan {
+            cells: [(0, 0), (0, 1)],
+        }];
+        assert!(puzzle_to_fpuzzles(Grid::EMPTY, &greater_than, false).is_err());
+    }
+}
@@ -0,0 +1,423 @@
+//! A lenient parser for grids typed or pasted by hand, which reports suspicious input instead of
+//! silently accepting it.
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    io::{Diagnostic, Parsed, PuzzleRecord},
+    sudoku::{Grid, PencilMarks},
+};
+
+/// How a char in the input is treated.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    /// 1-9.
+    Digit(u8),
+
+    /// An explicit blank cell, e.g. `.` or `0`.
+    Blank,
+
+    /// Part of a border or separator, e.g. `|`, `-` or box-drawing chars.
+    Border,
+
+    /// Spaces and tabs, which may either be blanks or separators depending on the layout.
+    Space,
+
+    /// Anything else, which is treated as a blank, but is likely a typo.
+    Suspicious,
+}
+
+/// A cell read from the input, with the 1-based (line, col) it came from.
+struct SourceCell {
+    class: CharClass,
+    c: char,
+    line: usize,
+    col: usize,
+}
+
+/// Parses a grid in any of the layouts accepted by [read_to_grid](crate::io::read_to_grid).
+///
+/// If strict is true, then any warning becomes an error.
+pub fn parse_grid_text(input: &str, strict: bool) -> Result<Parsed<PuzzleRecord>> {
+    let lines = input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim_end_matches('\r')))
+        .collect::<Vec<_>>();
+
+    let mut rows = lines
+        .iter()
+        .copied()
+        .filter(|(_, line)| is_row_line(line))
+        .collect::<Vec<_>>();
+    if rows.len() != 1 && rows.len() != 9 {
+        // A row of blanks written as spaces without borders, e.g. by `gen -n`, is only spaces.
+        let with_blank_rows = lines
+            .iter()
+            .copied()
+            .filter(|(_, line)| is_row_line(line) || is_blank_row_line(line))
+            .collect::<Vec<_>>();
+        if with_blank_rows.len() == 9 {
+            rows = with_blank_rows;
+        }
+    }
+
+    let mut warnings = Vec::new();
+
+    let (grid, pencil_marks) = if let [(line_number, line)] = rows.as_slice() {
+        let cells = cells(*line_number, line, |class| {
+            !matches!(class, CharClass::Border | CharClass::Space)
+        });
+        if cells.len() != 81 {
+            return Err(anyhow!(
+                "Invalid input: line {line_number} has {} cells, expected 81.",
+                cells.len()
+            ));
+        }
+
+        (cells_to_grid(&cells, &mut warnings), None)
+    } else if rows.len() == 9 {
+        if let Some(pencil_marks) = parse_pencil_marks(&rows, &mut warnings) {
+            (pencil_marks_to_grid(&pencil_marks), Some(pencil_marks))
+        } else {
+            let mut cells = Vec::new();
+            for (line_number, line) in &rows {
+                cells.extend(row_cells(*line_number, line, &mut warnings)?);
+            }
+
+            (cells_to_grid(&cells, &mut warnings), None)
+        }
+    } else {
+        return Err(anyhow!(
+            "Invalid input: found {} rows, expected 9 (or 1 row of 81 cells).",
+            rows.len()
+        ));
+    };
+
+    if strict && !warnings.is_empty() {
+        return Err(anyhow!(
+            "Invalid input: {}.",
+            warnings
+                .iter()
+                .map(Diagnostic::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        ));
+    }
+
+    Ok(Parsed {
+        value: PuzzleRecord {
+            puzzle: grid,
+            pencil_marks,
+            ..Default::default()
+        },
+        warnings,
+    })
+}
+
+/// Classifies a char in the input.
+fn classify(c: char) -> CharClass {
+    match c {
+        '1'..='9' => CharClass::Digit(c as u8 - b'0'),
+        '.' | '0' | '_' | 'x' | 'X' => CharClass::Blank,
+        '|' | '-' | '+' | '=' | ':' | '!' | '*' | '#' | '\'' | '/' | '\\' => CharClass::Border,
+        '\u{2500}'..='\u{257f}' => CharClass::Border,
+        ' ' | '\t' => CharClass::Space,
+        _ => CharClass::Suspicious,
+    }
+}
+
+/// Verifies whether a line holds a row of cells, rather than being a separator or empty.
+///
+/// A row either has some cell, or is a row of blanks that are all spaces between borders, e.g.
+/// `|   |   |   |`. Lines of only borders and `.` with a horizontal rule, e.g. `.---.---.`, are
+/// separators.
+fn is_row_line(line: &str) -> bool {
+    let is_separator = line
+        .chars()
+        .all(|c| c == '.' || classify(c) == CharClass::Border)
+        && ["--", "==", "──", "━━", "══"]
+            .iter()
+            .any(|rule| line.contains(rule));
+    if is_separator {
+        return false;
+    }
+
+    let classes = line.chars().map(classify).collect::<Vec<_>>();
+
+    let has_cell = classes
+        .iter()
+        .any(|class| !matches!(class, CharClass::Border | CharClass::Space));
+    let has_border = classes.contains(&CharClass::Border);
+    let has_space = line.trim().chars().any(|c| classify(c) == CharClass::Space);
+
+    has_cell || (has_border && has_space)
+}
+
+/// Verifies whether a line is only spaces, which is a row of blanks if a grid is short of 9 rows.
+fn is_blank_row_line(line: &str) -> bool {
+    !line.is_empty() && line.chars().all(|c| classify(c) == CharClass::Space)
+}
+
+/// Gets the chars of a line that are kept by keep, as [SourceCell]s.
+fn cells(line_number: usize, line: &str, keep: impl Fn(CharClass) -> bool) -> Vec<SourceCell> {
+    line.chars()
+        .enumerate()
+        .map(|(index, c)| SourceCell {
+            class: classify(c),
+            c,
+            line: line_number,
+            col: index + 1,
+        })
+        .filter(|cell| keep(cell.class))
+        .collect()
+}
+
+/// Gets the 9 cells of a row.
+///
+/// Spaces are blanks if that gives exactly 9 cells (e.g. `|53 | 7 |   |`), or else they are
+/// separators (e.g. `5 3 . | . 7 .`). Rows that are short of 9 cells are padded with blanks, as
+/// trailing spaces are often trimmed by editors.
+fn row_cells(
+    line_number: usize,
+    line: &str,
+    warnings: &mut Vec<Diagnostic>,
+) -> Result<Vec<SourceCell>> {
+    let with_spaces = cells(line_number, line, |class| class != CharClass::Border);
+    if with_spaces.len() == 9 {
+        return Ok(with_spaces);
+    }
+
+    let without_spaces = cells(line_number, line, |class| {
+        !matches!(class, CharClass::Border | CharClass::Space)
+    });
+    if without_spaces.len() == 9 {
+        return Ok(without_spaces);
+    }
+
+    if with_spaces.len() < 9 {
+        warnings.push(Diagnostic {
+            line: line_number,
+            col: line.chars().count() + 1,
+            message: format!(
+                "row has {} cells, padded with blanks to 9",
+                with_spaces.len()
+            ),
+        });
+
+        let mut cells = with_spaces;
+        while cells.len() < 9 {
+            cells.push(SourceCell {
+                class: CharClass::Space,
+                c: ' ',
+                line: line_number,
+                col: line.chars().count() + 1,
+            });
+        }
+
+        return Ok(cells);
+    }
+
+    Err(anyhow!(
+        "Invalid input: line {line_number} has {} cells, expected 9.",
+        without_spaces.len()
+    ))
+}
+
+/// Converts 81 cells in reading order to a [Grid], warning about suspicious chars.
+fn cells_to_grid(cells: &[SourceCell], warnings: &mut Vec<Diagnostic>) -> Grid {
+    let mut grid = Grid::EMPTY;
+
+    for (index, cell) in cells.iter().enumerate() {
+        match cell.class {
+            CharClass::Digit(digit) => grid.set((index / 9, index % 9), digit),
+            CharClass::Suspicious => warnings.push(suspicious(cell)),
+            _ => (),
+        }
+    }
+
+    grid
+}
+
+/// Parses a grid of pencil marks, where every row has 9 groups of candidates separated by spaces
+/// or borders, e.g. `| 5 3 124 | 26 7 1268 | ...`.
+///
+/// A single candidate is regarded as a given. Returns None if the rows are not pencil marks, i.e.
+/// not every row has 9 groups, or no group has more than one candidate.
+fn parse_pencil_marks(
+    rows: &[(usize, &str)],
+    warnings: &mut Vec<Diagnostic>,
+) -> Option<PencilMarks> {
+    let groups = rows
+        .iter()
+        .map(|(line_number, line)| {
+            let mut groups: Vec<Vec<SourceCell>> = Vec::new();
+            let mut group = Vec::new();
+
+            for cell in cells(*line_number, line, |_| true) {
+                if matches!(cell.class, CharClass::Border | CharClass::Space) {
+                    if !group.is_empty() {
+                        groups.push(std::mem::take(&mut group));
+                    }
+                } else {
+                    group.push(cell);
+                }
+            }
+            if !group.is_empty() {
+                groups.push(group);
+            }
+
+            groups
+        })
+        .collect::<Vec<_>>();
+
+    if !groups.iter().all(|groups| groups.len() == 9)
+        || !groups.iter().flatten().any(|group| group.len() > 1)
+    {
+        return None;
+    }
+
+    let mut pencil_marks = [[0; 9]; 9];
+    for (row, groups) in groups.iter().enumerate() {
+        for (col, group) in groups.iter().enumerate() {
+            for cell in group {
+                match cell.class {
+                    CharClass::Digit(digit) => pencil_marks[row][col] |= 1 << digit,
+                    CharClass::Suspicious => warnings.push(suspicious(cell)),
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    Some(pencil_marks)
+}
+
+/// Converts [PencilMarks] to a [Grid], where only cells with a single candidate are filled in.
+fn pencil_marks_to_grid(pencil_marks: &PencilMarks) -> Grid {
+    Grid::new(pencil_marks.map(|row| {
+        row.map(|candidates| {
+            if candidates.count_ones() == 1 {
+                candidates.trailing_zeros() as u8
+            } else {
+                0
+            }
+        })
+    }))
+    .expect("pencil marks are 1-9")
+}
+
+/// Creates the warning for a suspicious char.
+fn suspicious(cell: &SourceCell) -> Diagnostic {
+    let hint = match cell.c {
+        'l' | 'I' | 'i' => " (did you mean 1?)",
+        'O' | 'o' | 'D' => " (did you mean 0?)",
+        'Z' | 'z' => " (did you mean 2?)",
+        'S' | 's' => " (did you mean 5?)",
+        'G' | 'b' => " (did you mean 6?)",
+        'B' => " (did you mean 8?)",
+        'g' | 'q' => " (did you mean 9?)",
+        _ => "",
+    };
+
+    Diagnostic {
+        line: cell.line,
+        col: cell.col,
+        message: format!("unexpected char {:?}{hint}, treated as blank", cell.c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROW_0: [u8; 9] = [5, 3, 0, 0, 7, 0, 0, 0, 0];
+    const ROW_8: [u8; 9] = [0, 0, 0, 0, 8, 0, 0, 7, 9];
+
+    fn parse(input: &str) -> Parsed<PuzzleRecord> {
+        parse_grid_text(input, false).unwrap()
+    }
+
+    #[test]
+    fn check_layouts() {
+        let inputs = [
+            // Single line.
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+            // Plain 9x9, with trailing blanks trimmed.
+            "53  7\n6  195\n 98    6\n8   6   3\n4  8 3  1\n7   2   6\n 6    28\n   419  5\n    8  79\n",
+            // Spaces between digits.
+            "5 3 . . 7 . . . .\n6 . . 1 9 5 . . .\n. 9 8 . . . . 6 .\n8 . . . 6 . . . 3\n4 . . 8 . 3 . . 1\n7 . . . 2 . . . 6\n. 6 . . . . 2 8 .\n. . . 4 1 9 . . 5\n. . . . 8 . . 7 9\n",
+            // ASCII border.
+            "+---+---+---+\n|53 | 7 |   |\n|6  |195|   |\n| 98|   | 6 |\n+---+---+---+\n|8  | 6 |  3|\n|4  |8 3|  1|\n|7  | 2 |  6|\n+---+---+---+\n| 6 |   |28 |\n|   |419|  5|\n|   | 8 | 79|\n+---+---+---+\n",
+            // Box-drawing border, with spaced cells.
+            "╔═══════╤═══════╤═══════╗\n║ 5 3 . │ . 7 . │ . . . ║\n║ 6 . . │ 1 9 5 │ . . . ║\n║ . 9 8 │ . . . │ . 6 . ║\n╟───────┼───────┼───────╢\n║ 8 . . │ . 6 . │ . . 3 ║\n║ 4 . . │ 8 . 3 │ . . 1 ║\n║ 7 . . │ . 2 . │ . . 6 ║\n╟───────┼───────┼───────╢\n║ . 6 . │ . . . │ 2 8 . ║\n║ . . . │ 4 1 9 │ . . 5 ║\n║ . . . │ . 8 . │ . 7 9 ║\n╚═══════╧═══════╧═══════╝\n",
+        ];
+
+        for input in inputs {
+            let parsed = parse(input);
+            assert_eq!(parsed.value.puzzle[0], ROW_0, "{input}");
+            assert_eq!(parsed.value.puzzle[8], ROW_8, "{input}");
+            assert_eq!(parsed.value.pencil_marks, None);
+        }
+
+        // Only the trimmed rows are padded.
+        assert_eq!(parse(inputs[1]).warnings.len(), 4);
+        assert!(parse(inputs[3]).warnings.is_empty());
+
+        // A row of blanks written as spaces, as by `gen -n`, is still a row.
+        let input = "53  7    \n         \n 98    6 \n8   6   3\n4  8 3  1\n7   2   6\n 6    28 \n   419  5\n    8  79\n";
+        let parsed = parse(input);
+        assert_eq!(parsed.value.puzzle[1], [0; 9]);
+        assert_eq!(parsed.value.puzzle[8], ROW_8);
+        assert!(parsed.warnings.is_empty());
+    }
+
+    #[test]
+    fn check_pencil_marks() {
+        let input = "\
+.----------------.----------------.----------------.
+| 5    3    124  | 26   7    2468 | 1489 149  248  |
+| 6    247  247  | 1    9    5    | 3478 34   2478 |
+| 12   9    8    | 23   34   24   | 1345 6    247  |
+:----------------+----------------+----------------:
+| 8    1257 1579 | 579  6    147  | 4579 2459 3    |
+| 4    25   2569 | 8    5    3    | 579  259  1    |
+| 7    15   1359 | 59   2    14   | 4589 459  6    |
+:----------------+----------------+----------------:
+| 139  6    13479| 357  35   78   | 2    8    47   |
+| 123  278  237  | 4    1    9    | 36   3    5    |
+| 123  245  2345 | 2356 8    26   | 146  7    9    |
+'----------------'----------------'----------------'
+";
+        let parsed = parse(input);
+
+        assert_eq!(parsed.value.puzzle[0], ROW_0);
+        assert_eq!(parsed.value.puzzle[8], ROW_8);
+
+        let pencil_marks = parsed.value.pencil_marks.unwrap();
+        assert_eq!(pencil_marks[0][2], 1 << 1 | 1 << 2 | 1 << 4);
+        assert_eq!(pencil_marks[0][0], 1 << 5);
+    }
+
+    #[test]
+    fn check_warnings() {
+        let input = "53..7....\n6..l95...\n.98....6.\n8...6...3\n4..8.3..1\n7...2...6\n.6....28.\n...419..5\n....8..79\n";
+
+        let parsed = parse(input);
+        assert_eq!(parsed.value.puzzle[1], [6, 0, 0, 0, 9, 5, 0, 0, 0]);
+        assert_eq!(
+            parsed.warnings,
+            vec![Diagnostic {
+                line: 2,
+                col: 4,
+                message: "unexpected char 'l' (did you mean 1?), treated as blank".into()
+            }]
+        );
+
+        let error = parse_grid_text(input, true).unwrap_err().to_string();
+        assert!(error.contains("line 2, col 4"));
+
+        assert!(parse_grid_text("123\n456\n", false).is_err());
+        assert!(parse_grid_text(&"1234567890\n".repeat(9), false).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+//! A port of the base64 flavour of [lz-string](https://github.com/pieroxy/lz-string), which is used
+//! by f-puzzles and SudokuPad to share puzzles.
+//!
+//! lz-string works on UTF-16 code units, so strings are converted to and from UTF-16 here.
+
+use anyhow::{Result, anyhow};
+
+/// The alphabet used by the base64 flavour, followed by the padding char.
+const BASE64_CHARS: &[u8; 65] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+
+/// Compresses a String into base64, the same as `LZString.compressToBase64()`.
+pub fn compress_to_base64(input: &str) -> String {
+    let input = input.encode_utf16().collect::<Vec<_>>();
+
+    let mut output = compress(&input, 6)
+        .into_iter()
+        .map(|value| char::from(BASE64_CHARS[value as usize]))
+        .collect::<String>();
+    while output.len() % 4 != 0 {
+        output.push('=');
+    }
+
+    output
+}
+
+/// Decompresses base64 into a String, the same as `LZString.decompressFromBase64()`.
+pub fn decompress_from_base64(input: &str) -> Result<String> {
+    let values = input
+        .trim_end_matches('=')
+        .bytes()
+        .map(|byte| {
+            BASE64_CHARS[..64]
+                .iter()
+                .position(|c| *c == byte)
+                .map(|value| value as u16)
+                .ok_or(anyhow!(
+                    "Invalid input: bad base64 char {:?}.",
+                    char::from(byte)
+                ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let output = decompress(&values, 32).ok_or(anyhow!("Invalid input: bad compressed data."))?;
+
+    String::from_utf16(&output).map_err(|_| anyhow!("Invalid input: bad compressed data."))
+}
+
+/// Writes bits into values of bits_per_value bits each, most significant bit first.
+struct BitWriter {
+    bits_per_value: u32,
+    values: Vec<u16>,
+    value: u16,
+    position: u32,
+}
+
+impl BitWriter {
+    fn new(bits_per_value: u32) -> Self {
+        Self {
+            bits_per_value,
+            values: Vec::new(),
+            value: 0,
+            position: 0,
+        }
+    }
+
+    /// Writes the lowest num_bits of bits, least significant bit first.
+    fn write(&mut self, mut bits: u32, num_bits: u32) {
+        for _ in 0..num_bits {
+            self.value = (self.value << 1) | (bits & 1) as u16;
+            if self.position == self.bits_per_value - 1 {
+                self.position = 0;
+                self.values.push(self.value);
+                self.value = 0;
+            } else {
+                self.position += 1;
+            }
+            bits >>= 1;
+        }
+    }
+
+    /// Pads the last partial value with 0s and gets all the values.
+    fn finish(mut self) -> Vec<u16> {
+        loop {
+            self.value <<= 1;
+            if self.position == self.bits_per_value - 1 {
+                self.values.push(self.value);
+                break;
+            }
+            self.position += 1;
+        }
+
+        self.values
+    }
+}
+
+/// Reads bits from values of bits_per_value bits each, most significant bit first.
+struct BitReader<'a> {
+    values: &'a [u16],
+    reset_value: u16,
+    value: u16,
+    position: u16,
+    index: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(values: &'a [u16], reset_value: u16) -> Self {
+        Self {
+            values,
+            reset_value,
+            value: values.first().copied().unwrap_or(0),
+            position: reset_value,
+            index: 1,
+        }
+    }
+
+    /// Reads num_bits bits, least significant bit first.
+    fn read(&mut self, num_bits: u32) -> u32 {
+        let mut bits = 0;
+
+        for power in 0..num_bits {
+            let bit = self.value & self.position != 0;
+            self.position >>= 1;
+            if self.position == 0 {
+                self.position = self.reset_value;
+                self.value = self.values.get(self.index).copied().unwrap_or(0);
+                self.index += 1;
+            }
+            bits |= (bit as u32) << power;
+        }
+
+        bits
+    }
+
+    /// Verifies whether all the values have been read.
+    fn is_exhausted(&self) -> bool {
+        self.index > self.values.len()
+    }
+}
+
+/// The shared state of the dictionary size and code width, which grows as entries are added.
+struct CodeWidth {
+    enlarge_in: u32,
+    num_bits: u32,
+}
+
+impl CodeWidth {
+    fn tick(&mut self) {
+        self.enlarge_in -= 1;
+        if self.enlarge_in == 0 {
+            self.enlarge_in = 1 << self.num_bits;
+            self.num_bits += 1;
+        }
+    }
+}
+
+fn compress(input: &[u16], bits_per_value: u32) -> Vec<u16> {
+    use std::collections::{HashMap, HashSet};
+
+    let mut dictionary: HashMap<Vec<u16>, u32> = HashMap::new();
+    let mut to_create: HashSet<Vec<u16>> = HashSet::new();
+    let mut dict_size = 3;
+    let mut width = CodeWidth {
+        enlarge_in: 2,
+        num_bits: 2,
+    };
+    let mut writer = BitWriter::new(bits_per_value);
+
+    let mut w: Vec<u16> = Vec::new();
+
+    // Writes the code for w, creating a new single char entry for it if needed.
+    let emit = |w: &Vec<u16>,
+                dictionary: &HashMap<Vec<u16>, u32>,
+                to_create: &mut HashSet<Vec<u16>>,
+                width: &mut CodeWidth,
+                writer: &mut BitWriter| {
+        if to_create.remove(w) {
+            if w[0] < 256 {
+                writer.write(0, width.num_bits);
+                writer.write(w[0] as u32, 8);
+            } else {
+                writer.write(1, width.num_bits);
+                writer.write(w[0] as u32, 16);
+            }
+            width.tick();
+        } else {
+            writer.write(dictionary[w], width.num_bits);
+        }
+        width.tick();
+    };
+
+    for c in input {
+        let c = vec![*c];
+        if !dictionary.contains_key(&c) {
+            dictionary.insert(c.clone(), dict_size);
+            dict_size += 1;
+            to_create.insert(c.clone());
+        }
+
+        let wc = [w.as_slice(), c.as_slice()].concat();
+        if dictionary.contains_key(&wc) {
+            w = wc;
+        } else {
+            emit(&w, &dictionary, &mut to_create, &mut width, &mut writer);
+            dictionary.insert(wc, dict_size);
+            dict_size += 1;
+            w = c;
+        }
+    }
+
+    if !w.is_empty() {
+        emit(&w, &dictionary, &mut to_create, &mut width, &mut writer);
+    }
+
+    // Mark the end of the stream.
+    writer.write(2, width.num_bits);
+
+    writer.finish()
+}
+
+fn decompress(values: &[u16], reset_value: u16) -> Option<Vec<u16>> {
+    let mut dictionary: Vec<Vec<u16>> = vec![vec![], vec![], vec![]];
+    let mut width = CodeWidth {
+        enlarge_in: 4,
+        num_bits: 3,
+    };
+    let mut reader = BitReader::new(values, reset_value);
+
+    let c = match reader.read(2) {
+        0 => reader.read(8) as u16,
+        1 => reader.read(16) as u16,
+        _ => return Some(Vec::new()),
+    };
+    dictionary.push(vec![c]);
+
+    let mut w = vec![c];
+    let mut output = vec![c];
+
+    loop {
+        if reader.is_exhausted() {
+            return None;
+        }
+
+        let mut code = reader.read(width.num_bits) as usize;
+        match code {
+            0 | 1 => {
+                let c = reader.read(if code == 0 { 8 } else { 16 }) as u16;
+                dictionary.push(vec![c]);
+                code = dictionary.len() - 1;
+                width.enlarge_in -= 1;
+            }
+            2 => return Some(output),
+            _ => (),
+        }
+
+        if width.enlarge_in == 0 {
+            width.enlarge_in = 1 << width.num_bits;
+            width.num_bits += 1;
+        }
+
+        let entry = if code < dictionary.len() {
+            dictionary[code].clone()
+        } else if code == dictionary.len() {
+            [w.as_slice(), &w[..1]].concat()
+        } else {
+            return None;
+        };
+
+        output.extend_from_slice(&entry);
+        dictionary.push([w.as_slice(), &entry[..1]].concat());
+        w = entry;
+
+        width.tick();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_known_values() {
+        // Reference output of LZString.compressToBase64().
+        assert_eq!(compress_to_base64(""), "Q===");
+        assert_eq!(decompress_from_base64("Q===").unwrap(), "");
+    }
+
+    #[test]
+    fn check_round_trip() {
+        for input in [
+            "a",
+            "hello, hello, hello world",
+            r#"{"size":9,"grid":[[{},{},{}],[{"value":5,"given":true}]]}"#,
+            "ünïcödé ✓ 数独",
+            &"abcabcabcabcabcabc".repeat(100),
+        ] {
+            let compressed = compress_to_base64(input);
+            assert_eq!(compressed.len() % 4, 0);
+            assert_eq!(decompress_from_base64(&compressed).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn check_bad_input() {
+        assert!(decompress_from_base64("!!!!").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+//! A minimal PDF writer for printable puzzle booklets.
+//!
+//! Only the standard Helvetica fonts are used, so nothing needs to be embedded, and the output only
+//! depends on its input.
+
+use crate::{io::PuzzleRecord, sudoku::Grid};
+
+/// The width and height of an A4 page, in points.
+const PAGE_WIDTH: f64 = 595.0;
+const PAGE_HEIGHT: f64 = 842.0;
+
+/// The space around the page contents.
+const MARGIN: f64 = 40.0;
+
+/// The space at the top of every page for the heading.
+const HEADING_HEIGHT: f64 = 30.0;
+
+/// The space above every grid for its label.
+const LABEL_HEIGHT: f64 = 20.0;
+
+/// The number of solutions on every page of the answer key.
+const ANSWERS_PER_PAGE: usize = 12;
+
+/// Converts puzzles to a PDF booklet, with per_page puzzles on every page, followed by an answer
+/// key of the solutions.
+///
+/// Every puzzle is labelled with its number, and with its difficulty if it is in its [Metadata].
+/// Records without a solution are left out of the answer key.
+///
+/// [Metadata]: crate::io::Metadata
+pub fn records_to_pdf_book(records: &[PuzzleRecord], per_page: usize) -> String {
+    let per_page = per_page.max(1);
+    let mut pages = Vec::new();
+
+    for (page_index, chunk) in records.chunks(per_page).enumerate() {
+        let slots = chunk
+            .iter()
+            .enumerate()
+            .map(|(index, record)| {
+                let number = page_index * per_page + index + 1;
+                let label = match &record.metadata.difficulty {
+                    Some(difficulty) => format!("{number}. {difficulty}"),
+                    None => format!("{number}."),
+                };
+
+                (label, record.puzzle, None)
+            })
+            .collect::<Vec<_>>();
+
+        pages.push(page_content("Puzzles", &slots, per_page, pages.len() + 1));
+    }
+
+    let answers = records
+        .iter()
+        .enumerate()
+        .filter_map(|(index, record)| {
+            record
+                .solution
+                .map(|solution| (format!("{}.", index + 1), record.puzzle, Some(solution)))
+        })
+        .collect::<Vec<_>>();
+
+    for chunk in answers.chunks(ANSWERS_PER_PAGE) {
+        pages.push(page_content(
+            "Answers",
+            chunk,
+            ANSWERS_PER_PAGE,
+            pages.len() + 1,
+        ));
+    }
+
+    document(&pages)
+}
+
+/// Lays out the grids of a page in a table of slots, and draws them along with the heading and
+/// page number.
+///
+/// Every slot has a label, the puzzle, and the solution to draw over it, if any.
+fn page_content(
+    heading: &str,
+    slots: &[(String, Grid, Option<Grid>)],
+    per_page: usize,
+    page_number: usize,
+) -> String {
+    let cols = per_page.isqrt();
+    let rows = per_page.div_ceil(cols);
+
+    let slot_width = (PAGE_WIDTH - MARGIN * 2.0) / cols as f64;
+    let slot_height = (PAGE_HEIGHT - MARGIN * 2.0 - HEADING_HEIGHT) / rows as f64;
+    let size = (slot_width - 20.0).min(slot_height - LABEL_HEIGHT - 10.0);
+
+    let mut content = text(MARGIN, PAGE_HEIGHT - MARGIN - 16.0, "F2", 18.0, heading);
+    content.push_str(&text(
+        PAGE_WIDTH / 2.0 - 4.0,
+        MARGIN / 2.0,
+        "F1",
+        10.0,
+        &page_number.to_string(),
+    ));
+
+    for (index, (label, puzzle, solution)) in slots.iter().enumerate() {
+        let (row, col) = (index / cols, index % cols);
+        let x = MARGIN + col as f64 * slot_width + (slot_width - size) / 2.0;
+        let top = PAGE_HEIGHT - MARGIN - HEADING_HEIGHT - row as f64 * slot_height;
+
+        content.push_str(&text(x, top - 14.0, "F2", 12.0, label));
+        content.push_str(&grid_content(
+            x,
+            top - LABEL_HEIGHT - size,
+            size,
+            puzzle,
+            solution.as_ref(),
+        ));
+    }
+
+    content
+}
+
+/// Draws a grid with its bottom left corner at (x, y). Givens are drawn in bold, and the digits of
+/// the solution (if any) that fill in the blanks are drawn in grey.
+fn grid_content(x: f64, y: f64, size: f64, puzzle: &Grid, solution: Option<&Grid>) -> String {
+    let cell = size / 9.0;
+    let mut content = String::new();
+
+    for index in 0..=9 {
+        let width = if index % 3 == 0 { 2.0 } else { 0.5 };
+        let offset = index as f64 * cell;
+        content.push_str(&format!(
+            "{width:.1} w {:.2} {:.2} m {:.2} {:.2} l S {:.2} {:.2} m {:.2} {:.2} l S\n",
+            x + offset,
+            y,
+            x + offset,
+            y + size,
+            x,
+            y + offset,
+            x + size,
+            y + offset
+        ));
+    }
+
+    let font_size = cell * 0.65;
+    for row in 0..9 {
+        for col in 0..9 {
+            let (digit, font, gray) = match (puzzle[row][col], solution) {
+                (0, Some(solution)) if solution[row][col] != 0 => (solution[row][col], "F1", 0.45),
+                (0, _) => continue,
+                (given, _) => (given, "F2", 0.0),
+            };
+
+            // Helvetica digits are 0.556 em wide, and about 0.7 em tall.
+            let digit_x = x + (col as f64 + 0.5) * cell - font_size * 0.278;
+            let digit_y = y + (8 - row) as f64 * cell + cell / 2.0 - font_size * 0.35;
+
+            content.push_str(&format!("{gray:.2} g\n"));
+            content.push_str(&text(digit_x, digit_y, font, font_size, &digit.to_string()));
+            content.push_str("0 g\n");
+        }
+    }
+
+    content
+}
+
+/// Draws a line of text with its baseline starting at (x, y).
+fn text(x: f64, y: f64, font: &str, size: f64, text: &str) -> String {
+    let escaped = text
+        .chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{c}"),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect::<String>();
+
+    format!("BT /{font} {size:.2} Tf {x:.2} {y:.2} Td ({escaped}) Tj ET\n")
+}
+
+/// Assembles the page contents into a PDF document.
+fn document(pages: &[String]) -> String {
+    // Objects 1-4 are the catalog, the page tree and the fonts, then every page is followed by its
+    // content stream.
+    let page_ids = (0..pages.len())
+        .map(|index| 5 + index * 2)
+        .collect::<Vec<_>>();
+
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{id} 0 R"))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+
+    for (content, id) in pages.iter().zip(page_ids) {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            id + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{content}endstream",
+            content.len()
+        ));
+    }
+
+    let mut output = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(output.len());
+        output.push_str(&format!("{} 0 obj\n{object}\nendobj\n", index + 1));
+    }
+
+    let xref_offset = output.len();
+    output.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        output.push_str(&format!("{offset:010} 00000 n \n"));
+    }
+    output.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+        objects.len() + 1
+    ));
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::Metadata;
+
+    #[test]
+    fn check_book() {
+        let record = PuzzleRecord {
+            puzzle: Grid::EMPTY,
+            solution: Some(Grid::new([[1; 9]; 9]).unwrap()),
+            metadata: Metadata {
+                difficulty: Some("Easy (1)".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let records = vec![record; 13];
+
+        let pdf = records_to_pdf_book(&records, 6);
+
+        // 3 pages of puzzles, and 2 pages of answers.
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.contains("/Count 5 >>"));
+        assert_eq!(pdf.matches("(Puzzles) Tj").count(), 3);
+        assert_eq!(pdf.matches("(Answers) Tj").count(), 2);
+        assert!(pdf.contains("(13. Easy \\(1\\)) Tj"));
+        assert!(pdf.ends_with("%%EOF\n"));
+
+        // Every xref entry points at its object.
+        let xref = &pdf[pdf.find("xref\n").unwrap()..];
+        for (index, line) in xref.lines().skip(3).take(14).enumerate() {
+            let offset = line[..10].parse::<usize>().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", index + 1)));
+        }
+
+        assert_eq!(pdf, records_to_pdf_book(&records, 6));
+    }
+}
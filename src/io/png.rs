@@ -0,0 +1,282 @@
+//! PNG rendering of grids, for the web and social media.
+
+use ab_glyph::{Font, FontVec, PxScale, point};
+use anyhow::{Result, anyhow};
+
+use crate::{
+    io::{PngFont, PngStyle, Rgb},
+    sudoku::{Grid, has_conflict},
+};
+
+/// The smallest and largest cell sizes, where digits are still legible, and images are not huge.
+const MIN_CELL_SIZE: u32 = 8;
+const MAX_CELL_SIZE: u32 = 512;
+
+/// The builtin 5x7 bitmap font for 1-9, one row per byte, where the lowest 5 bits are the pixels
+/// from left to right.
+#[rustfmt::skip]
+const GLYPHS: [[u8; 7]; 9] = [
+    [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+    [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+    [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+    [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+    [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+    [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+    [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+];
+
+/// An RGB image being drawn.
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, color: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: color.repeat((width * height) as usize),
+        }
+    }
+
+    /// Blends color over the pixel at (x, y), where coverage is between 0 (none) and 1 (opaque).
+    fn blend(&mut self, x: u32, y: u32, color: Rgb, coverage: f32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let coverage = coverage.clamp(0.0, 1.0);
+        let index = ((y * self.width + x) * 3) as usize;
+        for (channel, value) in self.pixels[index..index + 3].iter_mut().zip(color) {
+            *channel = (*channel as f32 * (1.0 - coverage) + value as f32 * coverage).round() as u8;
+        }
+    }
+
+    fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Rgb) {
+        for y in y..y + height {
+            for x in x..x + width {
+                self.blend(x, y, color, 1.0);
+            }
+        }
+    }
+}
+
+/// Converts a puzzle to a PNG image, with the digits in filled (if any) drawn over its blanks.
+pub fn grid_to_png(puzzle: &Grid, filled: Option<&Grid>, style: &PngStyle) -> Result<Vec<u8>> {
+    if !(MIN_CELL_SIZE..=MAX_CELL_SIZE).contains(&style.cell_size) {
+        return Err(anyhow!(
+            "Invalid input: cell size must be {MIN_CELL_SIZE}-{MAX_CELL_SIZE} pixels."
+        ));
+    }
+
+    let font = match &style.font {
+        PngFont::Builtin => None,
+        PngFont::TrueType(data) => Some(
+            FontVec::try_from_vec(data.clone()).map_err(|_| anyhow!("Invalid input: bad font."))?,
+        ),
+    };
+
+    let cell = style.cell_size;
+    let margin = cell / 4;
+    let size = cell * 9 + margin * 2;
+    let mut canvas = Canvas::new(size, size, style.background_color);
+
+    let mut grid = *puzzle;
+    if let Some(filled) = filled {
+        for pos in (0..81).map(|index| (index / 9, index % 9)) {
+            if grid[pos] == 0 {
+                grid.set(pos, filled[pos]);
+            }
+        }
+    }
+
+    for row in 0..9 {
+        for col in 0..9 {
+            if style.highlight_conflicts && has_conflict(&grid, (row, col)) {
+                canvas.fill_rect(
+                    margin + col as u32 * cell,
+                    margin + row as u32 * cell,
+                    cell,
+                    cell,
+                    style.conflict_color,
+                );
+            }
+        }
+    }
+
+    let thick = (cell / 16).max(2);
+    for index in 0..=9 {
+        let offset = margin + index * cell;
+        let (start, width) = if index % 3 == 0 {
+            (offset - thick / 2, thick)
+        } else {
+            (offset, 1)
+        };
+
+        let length = cell * 9 + thick;
+        let from = margin - thick / 2;
+        canvas.fill_rect(start, from, width, length, style.line_color);
+        canvas.fill_rect(from, start, length, width, style.line_color);
+    }
+
+    for row in 0..9 {
+        for col in 0..9 {
+            let (digit, color) = match puzzle[row][col] {
+                0 => (grid[row][col], style.filled_color),
+                given => (given, style.given_color),
+            };
+            if !(1..=9).contains(&digit) {
+                continue;
+            }
+
+            let center_x = margin + col as u32 * cell + cell / 2;
+            let center_y = margin + row as u32 * cell + cell / 2;
+
+            match &font {
+                None => draw_builtin_digit(&mut canvas, digit, center_x, center_y, cell, color),
+                Some(font) => {
+                    draw_font_digit(&mut canvas, font, digit, center_x, center_y, cell, color)
+                }
+            }
+        }
+    }
+
+    let mut output = Vec::new();
+    let mut encoder = png::Encoder::new(&mut output, size, size);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&canvas.pixels)?;
+    writer.finish()?;
+
+    Ok(output)
+}
+
+/// Draws a digit in the builtin bitmap font, centred at (x, y).
+fn draw_builtin_digit(canvas: &mut Canvas, digit: u8, x: u32, y: u32, cell: u32, color: Rgb) {
+    let scale = (cell * 3 / 5 / 7).max(1);
+    let left = x - 5 * scale / 2;
+    let top = y - 7 * scale / 2;
+
+    for (glyph_row, bits) in GLYPHS[digit as usize - 1].iter().enumerate() {
+        for glyph_col in 0..5 {
+            if bits & (0b10000 >> glyph_col) != 0 {
+                canvas.fill_rect(
+                    left + glyph_col * scale,
+                    top + glyph_row as u32 * scale,
+                    scale,
+                    scale,
+                    color,
+                );
+            }
+        }
+    }
+}
+
+/// Draws a digit in a TrueType font, centred at (x, y).
+fn draw_font_digit(
+    canvas: &mut Canvas,
+    font: &FontVec,
+    digit: u8,
+    x: u32,
+    y: u32,
+    cell: u32,
+    color: Rgb,
+) {
+    let glyph = font
+        .glyph_id(char::from(b'0' + digit))
+        .with_scale_and_position(PxScale::from(cell as f32 * 0.75), point(0.0, 0.0));
+    let Some(outlined) = font.outline_glyph(glyph) else {
+        return;
+    };
+
+    let bounds = outlined.px_bounds();
+    let left = x as f32 - bounds.width() / 2.0;
+    let top = y as f32 - bounds.height() / 2.0;
+
+    outlined.draw(|glyph_x, glyph_y, coverage| {
+        canvas.blend(
+            (left + glyph_x as f32) as u32,
+            (top + glyph_y as f32) as u32,
+            color,
+            coverage,
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Decodes a PNG image into its width and RGB pixels.
+    fn decode(data: &[u8]) -> (u32, Vec<u8>) {
+        let mut reader = png::Decoder::new(Cursor::new(data)).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+
+        (info.width, pixels)
+    }
+
+    fn pixel(image: &(u32, Vec<u8>), x: u32, y: u32) -> Rgb {
+        let index = ((y * image.0 + x) * 3) as usize;
+        image.1[index..index + 3].try_into().unwrap()
+    }
+
+    #[test]
+    fn check_png() {
+        let mut puzzle = Grid::EMPTY;
+        puzzle.set((0, 0), 1);
+        let mut filled = Grid::EMPTY;
+        filled.set((0, 1), 7);
+        filled.set((4, 4), 1);
+        filled.set((4, 5), 1);
+
+        let style = PngStyle {
+            cell_size: 40,
+            ..Default::default()
+        };
+        let image = decode(&grid_to_png(&puzzle, Some(&filled), &style).unwrap());
+
+        // 9 cells, and a margin of 10 on both sides.
+        assert_eq!(image.0, 380);
+
+        // The stem of 1, and the top bar of 7.
+        assert_eq!(pixel(&image, 30, 22), style.given_color);
+        assert_eq!(pixel(&image, 64, 21), style.filled_color);
+
+        // Blank cells are left as they are, and the repeated 1s in row 4 are highlighted.
+        assert_eq!(pixel(&image, 110, 110), style.background_color);
+        assert_eq!(pixel(&image, 172, 172), style.conflict_color);
+        assert_eq!(pixel(&image, 24, 40), style.background_color);
+
+        assert!(
+            grid_to_png(
+                &puzzle,
+                None,
+                &PngStyle {
+                    font: PngFont::TrueType(vec![1, 2, 3]),
+                    ..Default::default()
+                }
+            )
+            .is_err()
+        );
+        assert!(
+            grid_to_png(
+                &puzzle,
+                None,
+                &PngStyle {
+                    cell_size: 0,
+                    ..Default::default()
+                }
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! The SadMan Software Sudoku formats: `.sdk` for a single puzzle with metadata, and `.sdm` for
+//! many puzzles, one per line.
+
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    io::{Metadata, PuzzleRecord},
+    sudoku::Grid,
+};
+
+/// Parses a `.sdk` file.
+///
+/// Example of accepted input:
+///
+/// ```text
+/// [Puzzle]
+/// #AJane Doe
+/// #DA classic.
+/// #LEasy
+/// 53..7....
+/// 6..195...
+/// .98....6.
+/// 8...6...3
+/// 4..8.3..1
+/// 7...2...6
+/// .6....28.
+/// ...419..5
+/// ....8..79
+/// ```
+///
+/// Metadata lines start with # and a one-letter tag. Any section after the puzzle (e.g. `[State]`)
+/// is ignored.
+pub fn parse_sdk(input: &str) -> Result<PuzzleRecord> {
+    let mut metadata = Metadata::default();
+    let mut rows = Vec::new();
+
+    for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if line.starts_with('[') {
+            if line.eq_ignore_ascii_case("[puzzle]") {
+                continue;
+            }
+
+            // Only the puzzle section is of interest.
+            break;
+        }
+
+        if let Some(tag) = line.strip_prefix('#') {
+            let mut chars = tag.chars();
+            let key = chars.next();
+            let value = Some(chars.as_str().trim().to_string()).filter(|value| !value.is_empty());
+
+            match key {
+                Some('A') => metadata.author = value,
+                Some('D') => metadata.description = value,
+                Some('C') => metadata.comment = value,
+                Some('B') => metadata.date = value,
+                Some('S') => metadata.source = value,
+                Some('U') => metadata.source_url = value,
+                Some('L') => metadata.difficulty = value,
+                _ => (),
+            }
+
+            continue;
+        }
+
+        if line.chars().count() != 9 || !line.chars().all(is_sdk_cell) {
+            return Err(anyhow!("Invalid input: bad .sdk row {line:?}."));
+        }
+        rows.push(line);
+    }
+
+    if rows.len() != 9 {
+        return Err(anyhow!("Invalid input: .sdk puzzle must have 9 rows."));
+    }
+
+    Ok(PuzzleRecord {
+        puzzle: Grid::from_str(&rows.concat())?,
+        metadata,
+        ..Default::default()
+    })
+}
+
+/// Converts a [PuzzleRecord] to a `.sdk` file.
+pub fn record_to_sdk(record: &PuzzleRecord) -> String {
+    let metadata = &record.metadata;
+
+    let mut output = String::from("[Puzzle]\n");
+    for (tag, value) in [
+        ('A', &metadata.author),
+        ('D', &metadata.description),
+        ('C', &metadata.comment),
+        ('B', &metadata.date),
+        ('S', &metadata.source),
+        ('U', &metadata.source_url),
+        ('L', &metadata.difficulty),
+    ] {
+        if let Some(value) = value {
+            output.push_str(&format!("#{tag}{}\n", value.replace('\n', " ")));
+        }
+    }
+
+    for row in record.puzzle.rows() {
+        output.extend(row.map(|digit| {
+            if digit == 0 {
+                '.'
+            } else {
+                char::from(b'0' + digit)
+            }
+        }));
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Parses a `.sdm` file, where every non-empty line is an 81-char puzzle in reading order.
+pub fn parse_sdm(input: &str) -> Result<Vec<PuzzleRecord>> {
+    input
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| {
+            if line.chars().count() != 81 || !line.chars().all(is_sdk_cell) {
+                return Err(anyhow!(
+                    "Invalid input: bad .sdm puzzle on line {}.",
+                    index + 1
+                ));
+            }
+
+            Ok(PuzzleRecord::from(Grid::from_str(line)?))
+        })
+        .collect()
+}
+
+/// Converts a [PuzzleRecord] to a line of a `.sdm` file. Metadata is not kept.
+pub fn record_to_sdm(record: &PuzzleRecord) -> String {
+    let mut line = record
+        .puzzle
+        .cells()
+        .map(|digit| char::from(b'0' + digit))
+        .collect::<String>();
+    line.push('\n');
+
+    line
+}
+
+/// Verifies whether a char is a valid cell, either a digit or a blank.
+pub fn is_sdk_cell(c: char) -> bool {
+    c.is_ascii_digit() || c == '.'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDK: &str = "[Puzzle]
+#AJane Doe
+#DA classic.
+#LEasy
+53..7....
+6..195...
+.98....6.
+8...6...3
+4..8.3..1
+7...2...6
+.6....28.
+...419..5
+....8..79
+[State]
+534678912
+";
+
+    #[test]
+    fn check_sdk() {
+        let record = parse_sdk(SDK).unwrap();
+
+        assert_eq!(record.puzzle[0], [5, 3, 0, 0, 7, 0, 0, 0, 0]);
+        assert_eq!(record.puzzle[8], [0, 0, 0, 0, 8, 0, 0, 7, 9]);
+        assert_eq!(record.metadata.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(record.metadata.description.as_deref(), Some("A classic."));
+        assert_eq!(record.metadata.difficulty.as_deref(), Some("Easy"));
+        assert_eq!(record.metadata.comment, None);
+
+        assert_eq!(parse_sdk(&record_to_sdk(&record)).unwrap(), record);
+
+        assert!(parse_sdk(&SDK.replace("53..7....", "53..7...")).is_err());
+        assert!(parse_sdk(&SDK.replace("53..7....", "53..7...x")).is_err());
+    }
+
+    #[test]
+    fn check_sdm() {
+        let input = "\
+530070000600195000098000060800060003400803001700020006060000280000419005000080079
+003020600900305001001806400008102900700000008006708200002609500800203009005010300
+";
+        let records = parse_sdm(input).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].puzzle[0], [5, 3, 0, 0, 7, 0, 0, 0, 0]);
+        assert_eq!(records[1].puzzle[8], [0, 0, 5, 0, 1, 0, 3, 0, 0]);
+
+        assert_eq!(records.iter().map(record_to_sdm).collect::<String>(), input);
+
+        assert!(parse_sdm("12345").is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! The Simple Sudoku `.ss` format, which splits the squares with `|` and `-` separators.
+
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+
+use crate::{io::PuzzleRecord, sudoku::Grid};
+
+/// Parses a `.ss` file.
+///
+/// Example of accepted input:
+///
+/// ```text
+/// 53.|.7.|...
+/// 6..|195|...
+/// .98|...|.6.
+/// -----------
+/// 8..|.6.|..3
+/// 4..|8.3|..1
+/// 7..|.2.|..6
+/// -----------
+/// .6.|...|28.
+/// ...|419|..5
+/// ...|.8.|.79
+/// ```
+///
+/// Blanks can be any of `.`, `0`, `X` or `x`. Separator lines, made up of only `-`, `+`, `*`, `|`
+/// and spaces, are skipped. Separators within rows are optional.
+pub fn parse_ss(input: &str) -> Result<PuzzleRecord> {
+    let mut cells = String::new();
+    let mut rows = 0;
+
+    for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if line.chars().all(|c| "-+*| ".contains(c)) {
+            continue;
+        }
+
+        let row = line
+            .chars()
+            .filter(|c| *c != '|' && *c != ' ')
+            .map(|c| if c == 'X' || c == 'x' { '.' } else { c })
+            .collect::<String>();
+        if row.chars().count() != 9 || !row.chars().all(|c| c.is_ascii_digit() || ".Xx".contains(c))
+        {
+            return Err(anyhow!("Invalid input: bad .ss row {line:?}."));
+        }
+
+        cells.push_str(&row);
+        rows += 1;
+    }
+
+    if rows != 9 {
+        return Err(anyhow!("Invalid input: .ss puzzle must have 9 rows."));
+    }
+
+    Ok(PuzzleRecord::from(Grid::from_str(&cells)?))
+}
+
+/// Converts a [PuzzleRecord] to a `.ss` file. Metadata is not kept.
+pub fn record_to_ss(record: &PuzzleRecord) -> String {
+    let mut output = String::new();
+
+    for (index, row) in record.puzzle.rows().enumerate() {
+        if index == 3 || index == 6 {
+            output.push_str("-----------\n");
+        }
+
+        for (col, digit) in row.iter().enumerate() {
+            if col == 3 || col == 6 {
+                output.push('|');
+            }
+            output.push(if *digit == 0 {
+                '.'
+            } else {
+                char::from(b'0' + digit)
+            });
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SS: &str = "\
+53.|.7.|...
+6..|195|...
+.98|...|.6.
+-----------
+8..|.6.|..3
+4..|8.3|..1
+7..|.2.|..6
+-----------
+.6.|...|28.
+...|419|..5
+...|.8.|.79
+";
+
+    #[test]
+    fn check_ss() {
+        let record = parse_ss(SS).unwrap();
+
+        assert_eq!(record.puzzle[0], [5, 3, 0, 0, 7, 0, 0, 0, 0]);
+        assert_eq!(record.puzzle[8], [0, 0, 0, 0, 8, 0, 0, 7, 9]);
+
+        assert_eq!(record_to_ss(&record), SS);
+
+        // Separators are optional, and X is a blank too.
+        let plain = SS
+            .replace('|', "")
+            .replace("-----------\n", "")
+            .replace('.', "X");
+        assert_eq!(parse_ss(&plain).unwrap(), record);
+
+        assert!(parse_ss(&SS.replace("53.|", "53|")).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+//! SVG rendering of puzzles, for print.
+//!
+//! The output only depends on the [PuzzleRecord], so that it can be compared against golden files.
+
+use crate::{
+    io::PuzzleRecord,
+    sudoku::GridPos,
+    variant::{Constraint, Line},
+};
+
+/// The width and height of a cell.
+const CELL: usize = 48;
+
+/// The space around the grid, which holds sandwich sums.
+const MARGIN: usize = 32;
+
+/// The width and height of the whole image.
+const SIZE: usize = CELL * 9 + MARGIN * 2;
+
+/// The colour of digits filled in by the solver, to tell them apart from the givens.
+const SOLVED_COLOR: &str = "#1a5fb4";
+
+/// The colour of thermometers and arrows.
+const DECORATION_COLOR: &str = "#b0b0b0";
+
+/// Converts a [PuzzleRecord] to an SVG image.
+///
+/// The givens of the puzzle are drawn in bold, and the digits of the solution (if any) that fill
+/// in the blanks are drawn in blue. The pencil marks (if any) are drawn in cells that are still
+/// blank. [Constraint]s are drawn as they are in f-puzzles, where sandwich sums are outside the
+/// grid.
+pub fn record_to_svg(record: &PuzzleRecord) -> String {
+    let mut output = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SIZE}\" height=\"{SIZE}\" \
+         viewBox=\"0 0 {SIZE} {SIZE}\" font-family=\"sans-serif\">\n"
+    );
+    output.push_str(&format!(
+        "<rect width=\"{SIZE}\" height=\"{SIZE}\" fill=\"white\"/>\n"
+    ));
+
+    // Lines and circles go under the grid lines, and edge clues go over them.
+    for constraint in &record.constraints {
+        if constraint.is_well_formed() && constraint.edge().is_none() {
+            output.push_str(&constraint_to_svg(constraint));
+        }
+    }
+
+    output.push_str(&grid_lines_to_svg());
+
+    for constraint in &record.constraints {
+        if constraint.is_well_formed() && constraint.edge().is_some() {
+            output.push_str(&constraint_to_svg(constraint));
+        }
+    }
+
+    for row in 0..9 {
+        for col in 0..9 {
+            output.push_str(&cell_to_svg(record, (row, col)));
+        }
+    }
+
+    output.push_str("</svg>\n");
+
+    output
+}
+
+/// Gets the centre of a cell.
+fn center((row, col): GridPos) -> (usize, usize) {
+    (
+        MARGIN + col * CELL + CELL / 2,
+        MARGIN + row * CELL + CELL / 2,
+    )
+}
+
+/// Draws the thin cell lines and the thick box lines.
+fn grid_lines_to_svg() -> String {
+    let mut output = String::new();
+
+    for index in (0..=9).filter(|index| index % 3 != 0) {
+        let offset = MARGIN + index * CELL;
+        let end = MARGIN + 9 * CELL;
+        output.push_str(&format!(
+            "<path d=\"M{offset} {MARGIN}V{end}M{MARGIN} {offset}H{end}\" stroke=\"black\" \
+             stroke-width=\"1\"/>\n"
+        ));
+    }
+
+    for index in [0, 3, 6, 9] {
+        let offset = MARGIN + index * CELL;
+        let end = MARGIN + 9 * CELL;
+        output.push_str(&format!(
+            "<path d=\"M{offset} {MARGIN}V{end}M{MARGIN} {offset}H{end}\" stroke=\"black\" \
+             stroke-width=\"3\" stroke-linecap=\"square\"/>\n"
+        ));
+    }
+
+    output
+}
+
+/// Draws a [Constraint].
+fn constraint_to_svg(constraint: &Constraint) -> String {
+    match constraint {
+        Constraint::Thermometer { cells } => {
+            let (x, y) = center(cells[0]);
+            format!(
+                "<circle cx=\"{x}\" cy=\"{y}\" r=\"18\" fill=\"{DECORATION_COLOR}\"/>\n\
+                 <polyline points=\"{}\" fill=\"none\" stroke=\"{DECORATION_COLOR}\" \
+                 stroke-width=\"14\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n",
+                points(cells)
+            )
+        }
+        Constraint::Arrow { circle, arrow } => {
+            let mut cells = vec![circle[circle.len() - 1]];
+            cells.extend(arrow);
+
+            let tip = center(cells[cells.len() - 1]);
+            let before_tip = center(cells[cells.len() - 2]);
+            let (dx, dy) = (
+                tip.0 as f64 - before_tip.0 as f64,
+                tip.1 as f64 - before_tip.1 as f64,
+            );
+            let length = (dx * dx + dy * dy).sqrt();
+            let (dx, dy) = (dx / length, dy / length);
+            let head = |side: f64| {
+                format!(
+                    "{:.1},{:.1}",
+                    tip.0 as f64 - 12.0 * dx - 8.0 * side * dy,
+                    tip.1 as f64 - 12.0 * dy + 8.0 * side * dx
+                )
+            };
+
+            let min_x = circle.iter().map(|(_, col)| *col).min().unwrap_or(0);
+            let max_x = circle.iter().map(|(_, col)| *col).max().unwrap_or(0);
+            let min_y = circle.iter().map(|(row, _)| *row).min().unwrap_or(0);
+            let max_y = circle.iter().map(|(row, _)| *row).max().unwrap_or(0);
+
+            format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{DECORATION_COLOR}\" \
+                 stroke-width=\"3\" stroke-linejoin=\"round\"/>\n\
+                 <polyline points=\"{} {},{} {}\" fill=\"none\" stroke=\"{DECORATION_COLOR}\" \
+                 stroke-width=\"3\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n\
+                 <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"20\" fill=\"white\" \
+                 stroke=\"{DECORATION_COLOR}\" stroke-width=\"3\"/>\n",
+                points(&cells),
+                head(1.0),
+                tip.0,
+                tip.1,
+                head(-1.0),
+                MARGIN + min_x * CELL + 4,
+                MARGIN + min_y * CELL + 4,
+                (max_x - min_x + 1) * CELL - 8,
+                (max_y - min_y + 1) * CELL - 8,
+            )
+        }
+        Constraint::Sandwich { line, sum } => {
+            let (x, y, anchor) = match line {
+                Line::Row(row) => (MARGIN - 8, center((*row, 0)).1, "end"),
+                Line::Col(col) => (center((0, *col)).0, MARGIN - 12, "middle"),
+            };
+            format!(
+                "<text x=\"{x}\" y=\"{y}\" font-size=\"18\" text-anchor=\"{anchor}\" \
+                 dominant-baseline=\"central\">{sum}</text>\n"
+            )
+        }
+        Constraint::WhiteDot { cells } | Constraint::BlackDot { cells } => {
+            let (x, y) = midpoint(*cells);
+            let fill = if matches!(constraint, Constraint::WhiteDot { .. }) {
+                "white"
+            } else {
+                "black"
+            };
+            format!(
+                "<circle cx=\"{x}\" cy=\"{y}\" r=\"7\" fill=\"{fill}\" stroke=\"black\" \
+                 stroke-width=\"1.5\"/>\n"
+            )
+        }
+        Constraint::X { cells } | Constraint::V { cells } => {
+            let (x, y) = midpoint(*cells);
+            format!(
+                "<text x=\"{x}\" y=\"{y}\" font-size=\"18\" font-weight=\"bold\" \
+                 text-anchor=\"middle\" dominant-baseline=\"central\" stroke=\"white\" \
+                 stroke-width=\"4\" paint-order=\"stroke\">{}</text>\n",
+                constraint.name()
+            )
+        }
+        Constraint::GreaterThan { cells } => {
+            // The chevron points at the second (smaller) cell.
+            let (x, y) = midpoint(*cells);
+            let (greater, smaller) = (center(cells[0]), center(cells[1]));
+            let dx = (smaller.0 as isize - greater.0 as isize).signum();
+            let dy = (smaller.1 as isize - greater.1 as isize).signum();
+            let (x, y) = (x as isize, y as isize);
+
+            format!(
+                "<polyline points=\"{},{} {},{} {},{}\" fill=\"none\" stroke=\"black\" \
+                 stroke-width=\"2\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n",
+                x - 5 * dx - 7 * dy,
+                y - 5 * dy - 7 * dx,
+                x + 5 * dx,
+                y + 5 * dy,
+                x - 5 * dx + 7 * dy,
+                y - 5 * dy + 7 * dx,
+            )
+        }
+    }
+}
+
+/// Draws the digit, or the pencil marks, of a cell.
+fn cell_to_svg(record: &PuzzleRecord, (row, col): GridPos) -> String {
+    let (x, y) = center((row, col));
+
+    let given = record.puzzle[row][col];
+    if given != 0 {
+        return format!(
+            "<text x=\"{x}\" y=\"{y}\" font-size=\"32\" font-weight=\"bold\" \
+             text-anchor=\"middle\" dominant-baseline=\"central\">{given}</text>\n"
+        );
+    }
+
+    let solved = record.solution.map_or(0, |solution| solution[row][col]);
+    if solved != 0 {
+        return format!(
+            "<text x=\"{x}\" y=\"{y}\" font-size=\"32\" fill=\"{SOLVED_COLOR}\" \
+             text-anchor=\"middle\" dominant-baseline=\"central\">{solved}</text>\n"
+        );
+    }
+
+    let candidates = record
+        .pencil_marks
+        .map_or(0, |pencil_marks| pencil_marks[row][col]);
+
+    (1..=9)
+        .filter(|digit| candidates & (1 << digit) != 0)
+        .map(|digit| {
+            // Candidates are laid out like a phone keypad.
+            let x = x + (digit - 1) % 3 * 14 - 14;
+            let y = y + (digit - 1) / 3 * 14 - 14;
+            format!(
+                "<text x=\"{x}\" y=\"{y}\" font-size=\"12\" fill=\"#666666\" \
+                 text-anchor=\"middle\" dominant-baseline=\"central\">{digit}</text>\n"
+            )
+        })
+        .collect()
+}
+
+/// Gets the midpoint of the edge between two adjacent cells.
+fn midpoint([a, b]: [GridPos; 2]) -> (usize, usize) {
+    let (a, b) = (center(a), center(b));
+
+    ((a.0 + b.0) / 2, (a.1 + b.1) / 2)
+}
+
+/// Gets the centres of cells as SVG points.
+fn points(cells: &[GridPos]) -> String {
+    cells
+        .iter()
+        .map(|pos| {
+            let (x, y) = center(*pos);
+            format!("{x},{y}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::Grid;
+
+    const PUZZLE: Grid = Grid::new([
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [0, 9, 8, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ])
+    .unwrap();
+
+    const SOLUTION: Grid = Grid::new([
+        [5, 3, 4, 6, 7, 8, 9, 1, 2],
+        [6, 7, 2, 1, 9, 5, 3, 4, 8],
+        [1, 9, 8, 3, 4, 2, 5, 6, 7],
+        [8, 5, 9, 7, 6, 1, 4, 2, 3],
+        [4, 2, 6, 8, 5, 3, 7, 9, 1],
+        [7, 1, 3, 9, 2, 4, 8, 5, 6],
+        [9, 6, 1, 5, 3, 7, 2, 8, 4],
+        [2, 8, 7, 4, 1, 9, 6, 3, 5],
+        [3, 4, 5, 2, 8, 6, 1, 7, 9],
+    ])
+    .unwrap();
+
+    #[test]
+    fn check_golden() {
+        let mut solution = SOLUTION;
+        for col in 0..9 {
+            solution.set((8, col), 0);
+        }
+
+        let mut pencil_marks = [[0; 9]; 9];
+        pencil_marks[8][0] = 1 << 2 | 1 << 3 | 1 << 9;
+
+        let record = PuzzleRecord {
+            puzzle: PUZZLE,
+            constraints: vec![
+                Constraint::Thermometer {
+                    cells: vec![(0, 2), (1, 2), (2, 3)],
+                },
+                Constraint::Arrow {
+                    circle: vec![(3, 1), (3, 2)],
+                    arrow: vec![(4, 2), (5, 3)],
+                },
+                Constraint::Sandwich {
+                    line: Line::Row(1),
+                    sum: 0,
+                },
+                Constraint::Sandwich {
+                    line: Line::Col(4),
+                    sum: 12,
+                },
+                Constraint::WhiteDot {
+                    cells: [(0, 0), (0, 1)],
+                },
+                Constraint::BlackDot {
+                    cells: [(6, 1), (7, 1)],
+                },
+                Constraint::X {
+                    cells: [(4, 6), (4, 7)],
+                },
+                Constraint::V {
+                    cells: [(7, 7), (7, 8)],
+                },
+                Constraint::GreaterThan {
+                    cells: [(5, 6), (5, 7)],
+                },
+                Constraint::GreaterThan {
+                    cells: [(7, 3), (6, 3)],
+                },
+            ],
+            solution: Some(solution),
+            pencil_marks: Some(pencil_marks),
+            ..Default::default()
+        };
+
+        let svg = record_to_svg(&record);
+        assert_eq!(svg, record_to_svg(&record));
+        assert_eq!(svg, include_str!("../../testdata/golden.svg"));
+    }
+}
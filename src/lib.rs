@@ -1,9 +1,66 @@
+//! Generates and solves Sudoku puzzles.
+//!
+//! Without the default `std` feature the crate is `no_std`, with the grid, validation, solving,
+//! counting and generating with a caller's [Random](prelude::Random) generator. The `alloc`
+//! feature adds variant rules, rating, finding every solution and generating puzzles of a
+//! difficulty. Files and the system's random numbers need `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod game;
+#[cfg(feature = "std")]
 mod io;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "alloc")]
+mod rating;
+#[cfg(feature = "serde")]
+pub mod serialize;
 mod sudoku;
+#[cfg(feature = "alloc")]
+mod variant;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub mod prelude {
+    #[cfg(feature = "std")]
+    pub use fastrand::Rng;
+
+    pub use super::sudoku::{
+        Aborted, Budget, Constraint, Grid, GridPos, InputError, PencilMarks, Puzzle, Random,
+        Solution, SolveStats, count_solutions, count_solutions_with_budget,
+        count_solutions_with_constraints, generate_with_budget, generate_with_rng, has_conflict,
+        has_unique_solution_with_constraints, solve_any, solve_any_with_budget,
+        solve_any_with_constraints, solve_any_with_stats,
+    };
+    #[cfg(feature = "std")]
+    pub use super::{
+        game::{Game, Move},
+        io::{
+            Diagnostic, FileFormat, GridStyle, Metadata, Parsed, PngFont, PngStyle, PuzzleRecord,
+            Rgb, parse_grid, read_fpuzzles, read_fpuzzles_from, read_game, read_game_from,
+            read_grid, read_grid_strict, read_puzzles, read_puzzles_from, read_to_grid,
+            read_to_grid_strict, read_variant_puzzle, read_variant_puzzle_from, render_grid,
+            write_book, write_book_to, write_fpuzzles, write_fpuzzles_to, write_game,
+            write_game_to, write_grid, write_grid_colored, write_grid_colored_to, write_grid_to,
+            write_grid_with_edge_clues, write_grid_with_edge_clues_to, write_png, write_png_to,
+            write_puzzles, write_puzzles_to, write_svg, write_svg_to, write_variant_puzzle,
+            write_variant_puzzle_to,
+        },
+        sudoku::{generate, generate_with_difficulty, generate_with_edge_clues},
+    };
+    #[cfg(feature = "alloc")]
     pub use super::{
-        io::{read_to_grid, write_grid},
-        sudoku::{Grid, Puzzle, Solution, generate, solve, solve_any},
+        rating::{Difficulty, Hint, Technique, hint, rate},
+        sudoku::{
+            generate_with_difficulty_and_budget, generate_with_difficulty_and_rng,
+            generate_with_edge_clues_and_budget, generate_with_edge_clues_and_rng, solve,
+            solve_with_budget, solve_with_constraints,
+        },
+        variant::{Line, Violation, validate},
     };
 }
@@ -1,14 +1,21 @@
-use std::path::PathBuf;
+use std::{io::IsTerminal, path::PathBuf, time::Duration};
 
 use anyhow::{Result, anyhow};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 use sudoku::prelude::*;
 
+mod play;
+mod serve;
+
 #[derive(Parser)]
 struct Args {
     #[command(subcommand)]
     command: Command,
+
+    /// When to colour grids written to the terminal.
+    #[arg(long, global = true, value_enum, default_value_t = Color::Auto)]
+    color: Color,
 }
 
 #[derive(Subcommand)]
@@ -26,6 +33,18 @@ enum Command {
         /// The character that represents a blank space.
         #[arg(short = 'b', long = "blank", default_value_t = ' ')]
         blank_char: char,
+
+        /// Generate a variant puzzle where edge clues replace most or all of the givens.
+        #[arg(short = 'e', long = "edge-clues")]
+        edge_clues: bool,
+
+        /// The format to write the puzzle in.
+        #[arg(short = 'f', long = "format", value_enum, default_value_t = Format::Grid)]
+        output_format: Format,
+
+        /// The longest time to spend generating, in milliseconds, omit for no limit.
+        #[arg(long)]
+        timeout: Option<u64>,
     },
     /// Solves a puzzle.
     Solve {
@@ -40,43 +59,528 @@ enum Command {
         /// Do not draw border to format the solution.
         #[arg(short = 'n', long = "no-border")]
         plain_output: bool,
+
+        /// The format to read the puzzle in, omit to guess from the file extension and content.
+        #[arg(short = 'F', long = "input-format", value_enum)]
+        input_format: Option<Format>,
+
+        /// Treat warnings about suspicious input (e.g. `l` for `1`) as errors.
+        #[arg(long)]
+        strict: bool,
+
+        /// The format to write the solution in.
+        #[arg(short = 'f', long = "format", value_enum, default_value_t = Format::Grid)]
+        output_format: Format,
+
+        /// Print the work each solve took (guesses, backtracks, search depth, etc.) to stderr.
+        #[arg(long)]
+        stats: bool,
+
+        /// The longest time to spend solving, in milliseconds, omit for no limit.
+        #[arg(long)]
+        timeout: Option<u64>,
+    },
+    /// Shows the next step to solve a puzzle, and the cells it depends on.
+    Hint {
+        /// The input file to read from, omit to read from stdin.
+        #[arg(short, long = "input")]
+        input_file: Option<PathBuf>,
+
+        /// Do not draw border to format the puzzle.
+        #[arg(short = 'n', long = "no-border")]
+        plain_output: bool,
+
+        /// The format to read the puzzle in, omit to guess from the file extension and content.
+        #[arg(short = 'F', long = "input-format", value_enum)]
+        input_format: Option<Format>,
+
+        /// Treat warnings about suspicious input (e.g. `l` for `1`) as errors.
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Plays a puzzle in the terminal.
+    Play {
+        /// The input file to read the puzzle from, omit to generate one.
+        #[arg(short, long = "input")]
+        input_file: Option<PathBuf>,
+
+        /// The file to save the game to when quitting, and to resume it from if it exists, in which
+        /// case --input cannot be given.
+        #[arg(short, long = "save")]
+        save_file: Option<PathBuf>,
+    },
+    /// Serves generate, solve, rate, hint, check and count as a JSON API over HTTP.
+    Serve {
+        /// The address to listen on.
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// The port to listen on.
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+
+        /// The number of requests to handle at once.
+        #[arg(short, long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+        threads: u16,
+
+        /// The longest time to work on a request, in milliseconds.
+        #[arg(long, default_value_t = 5000)]
+        timeout: u64,
+
+        /// The largest request body, in bytes.
+        #[arg(long, default_value_t = 65536)]
+        max_body: usize,
+
+        /// The largest number of solutions to count.
+        #[arg(long, default_value_t = 1000)]
+        max_count: usize,
+    },
+    /// Generates a printable PDF booklet of puzzles, with an answer key.
+    Book {
+        /// The output file to write to (overwrites), omit to write to stdout.
+        #[arg(short, long = "output")]
+        output_file: Option<PathBuf>,
+
+        /// The number of puzzles to generate.
+        #[arg(short, long, default_value_t = 12, value_parser = clap::value_parser!(u16).range(1..))]
+        count: u16,
+
+        /// The number of puzzles on every page.
+        #[arg(short, long, default_value_t = 6, value_parser = clap::value_parser!(u8).range(1..=12))]
+        per_page: u8,
+
+        /// The difficulty of the puzzles, where mixed goes from easy to expert.
+        #[arg(short, long, value_enum, default_value_t = BookDifficulty::Mixed)]
+        difficulty: BookDifficulty,
+
+        /// The longest time to spend generating each puzzle, in milliseconds.
+        #[arg(long, default_value_t = 60000)]
+        timeout: u64,
     },
 }
 
+/// When to colour grids.
+#[derive(Clone, Copy, ValueEnum)]
+enum Color {
+    /// When writing to a terminal, and NO_COLOR is not set.
+    Auto,
+    /// Even when writing to a pipe.
+    Always,
+    /// Plain text only.
+    Never,
+}
+
+impl Color {
+    /// Returns whether grids written to output_file (or stdout if None) are coloured. Files are
+    /// never coloured.
+    fn enabled(self, output_file: Option<&PathBuf>) -> bool {
+        output_file.is_none()
+            && match self {
+                Color::Auto => {
+                    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+                }
+                Color::Always => true,
+                Color::Never => false,
+            }
+    }
+}
+
+/// The difficulty of the puzzles in a book, see [Difficulty].
+#[derive(Clone, Copy, ValueEnum)]
+enum BookDifficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    Mixed,
+}
+
+/// The puzzle file formats, see [FileFormat].
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// 9x9 char grid, in any common layout when reading.
+    Grid,
+    /// SadMan Software .sdk, a single puzzle with metadata.
+    Sdk,
+    /// SadMan Software .sdm, one puzzle per line.
+    Sdm,
+    /// Simple Sudoku .ss.
+    Ss,
+    /// JSON variant puzzle description.
+    Variant,
+    /// f-puzzles / SudokuPad (JSON, compressed or a link).
+    Fpuzzles,
+    /// SVG image, for print (write only).
+    Svg,
+    /// PNG image (write only).
+    Png,
+}
+
+impl From<Format> for FileFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Grid => FileFormat::Grid,
+            Format::Sdk => FileFormat::Sdk,
+            Format::Sdm => FileFormat::Sdm,
+            Format::Ss => FileFormat::Ss,
+            Format::Variant => FileFormat::Variant,
+            Format::Fpuzzles => FileFormat::Fpuzzles,
+            Format::Svg => FileFormat::Svg,
+            Format::Png => FileFormat::Png,
+        }
+    }
+}
+
 fn main() -> Result<()> {
     let args = Args::parse();
+    let color = args.color;
 
     match args.command {
         Command::Gen {
             output_file,
             plain_output,
             blank_char,
-        } => gen_command(output_file, plain_output, blank_char)?,
+            edge_clues,
+            output_format,
+            timeout,
+        } => {
+            let color = color.enabled(output_file.as_ref());
+            gen_command(
+                output_file,
+                plain_output,
+                blank_char,
+                edge_clues,
+                output_format.into(),
+                color,
+                timeout.map(Duration::from_millis),
+            )?
+        }
         Command::Solve {
             input_file,
             output_file,
             plain_output,
-        } => solve_command(input_file, output_file, plain_output)?,
+            input_format,
+            strict,
+            output_format,
+            stats,
+            timeout,
+        } => {
+            let color = color.enabled(output_file.as_ref());
+            solve_command(
+                input_file,
+                output_file,
+                plain_output,
+                input_format.map(FileFormat::from),
+                strict,
+                output_format.into(),
+                color,
+                stats,
+                timeout.map(Duration::from_millis),
+            )?
+        }
+        Command::Hint {
+            input_file,
+            plain_output,
+            input_format,
+            strict,
+        } => hint_command(
+            input_file,
+            plain_output,
+            input_format.map(FileFormat::from),
+            strict,
+            color.enabled(None),
+        )?,
+        Command::Play {
+            input_file,
+            save_file,
+        } => play_command(input_file, save_file)?,
+        Command::Serve {
+            host,
+            port,
+            threads,
+            timeout,
+            max_body,
+            max_count,
+        } => serve::serve(
+            &format!("{host}:{port}"),
+            threads.into(),
+            serve::Limits {
+                max_body,
+                timeout: Duration::from_millis(timeout),
+                max_count,
+            },
+        )?,
+        Command::Book {
+            output_file,
+            count,
+            per_page,
+            difficulty,
+            timeout,
+        } => book_command(
+            output_file,
+            count.into(),
+            per_page.into(),
+            difficulty,
+            Duration::from_millis(timeout),
+        )?,
     }
 
     Ok(())
 }
 
 /// Executes the gen command.
-fn gen_command(output_file: Option<PathBuf>, plain_output: bool, blank_char: char) -> Result<()> {
-    let puzzle = generate();
+fn gen_command(
+    output_file: Option<PathBuf>,
+    plain_output: bool,
+    blank_char: char,
+    edge_clues: bool,
+    output_format: FileFormat,
+    color: bool,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let budget = timeout_budget(timeout);
+    let (puzzle, constraints) = if edge_clues {
+        generate_with_edge_clues_and_budget(&mut Rng::new(), &budget)
+    } else {
+        generate_with_budget(&mut Rng::new(), &budget).map(|puzzle| (puzzle, Vec::new()))
+    }
+    .map_err(|Aborted| timed_out(timeout))?;
+
+    let record = PuzzleRecord {
+        puzzle,
+        constraints,
+        ..Default::default()
+    };
+
+    if color && output_format == FileFormat::Grid && record.constraints.is_empty() {
+        return write_colored(&[record], !plain_output, &blank_char.to_string());
+    }
 
-    write_grid(puzzle, output_file, !plain_output, &blank_char.to_string())
+    write_puzzles(
+        &[record],
+        output_file,
+        output_format,
+        !plain_output,
+        &blank_char.to_string(),
+    )
 }
 
 /// Executes the solve command.
+#[allow(clippy::too_many_arguments)]
 fn solve_command(
     input_file: Option<PathBuf>,
     output_file: Option<PathBuf>,
     plain_output: bool,
+    input_format: Option<FileFormat>,
+    strict: bool,
+    output_format: FileFormat,
+    color: bool,
+    stats: bool,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let parsed = read_puzzles(input_file, input_format, strict)?;
+    for warning in parsed.warnings {
+        eprintln!("Warning: {warning}.");
+    }
+    let records = parsed.value;
+    let budget = timeout_budget(timeout);
+
+    // Coloured grids show the solved digits apart from the givens, like images.
+    let color = color
+        && output_format == FileFormat::Grid
+        && records.iter().all(|record| record.constraints.is_empty());
+
+    let solutions = records
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let (solution, solve_stats) =
+                solve_any_with_stats(record.puzzle, &record.constraints, &budget);
+            if stats {
+                eprintln!("Puzzle {}: {solve_stats}.", index + 1);
+            }
+            let solution = solution
+                .map_err(|Aborted| timed_out(timeout))?
+                .ok_or(anyhow!("No solution for puzzle {}.", index + 1))?;
+
+            // Images draw the solution over the givens, the other formats only hold the solution.
+            let (puzzle, solution) = if output_format.is_image() || color {
+                (record.puzzle, Some(solution))
+            } else {
+                (solution, None)
+            };
+
+            Ok(PuzzleRecord {
+                puzzle,
+                solution,
+                constraints: if output_format.holds_constraints() {
+                    record.constraints
+                } else {
+                    Vec::new()
+                },
+                metadata: record.metadata,
+                ..Default::default()
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if color {
+        return write_colored(&solutions, !plain_output, " ");
+    }
+
+    write_puzzles(&solutions, output_file, output_format, !plain_output, " ")
+}
+
+/// Creates the budget for a command with a timeout, or an unlimited one.
+fn timeout_budget(timeout: Option<Duration>) -> Budget {
+    timeout.map(Budget::with_timeout).unwrap_or_default()
+}
+
+/// The error of a command that ran out of its timeout.
+fn timed_out(timeout: Option<Duration>) -> anyhow::Error {
+    anyhow!(
+        "Timed out after {} ms.",
+        timeout.unwrap_or_default().as_millis()
+    )
+}
+
+/// Executes the hint command.
+fn hint_command(
+    input_file: Option<PathBuf>,
+    plain_output: bool,
+    input_format: Option<FileFormat>,
+    strict: bool,
+    color: bool,
+) -> Result<()> {
+    let parsed = read_puzzles(input_file, input_format, strict)?;
+    for warning in parsed.warnings {
+        eprintln!("Warning: {warning}.");
+    }
+
+    let record = match parsed.value.as_slice() {
+        [record] => record.clone(),
+        _ => return Err(anyhow!("Unsupported: hints need exactly one puzzle.")),
+    };
+    if !record.constraints.is_empty() {
+        return Err(anyhow!(
+            "Unsupported: hints cannot use variant constraints."
+        ));
+    }
+
+    let hint = hint(record.puzzle).ok_or(anyhow!(
+        "No hint for the puzzle, it is invalid or too hard for the known techniques."
+    ))?;
+
+    let mut filled = Grid::EMPTY;
+    filled.set(hint.pos, hint.digit);
+
+    if color {
+        let mut highlights = hint.cells.clone();
+        highlights.push(hint.pos);
+        write_grid_colored(
+            record.puzzle,
+            Some(filled),
+            &highlights,
+            None::<PathBuf>,
+            !plain_output,
+            " ",
+        )?;
+    } else {
+        write_grid(record.puzzle, None::<PathBuf>, !plain_output, " ")?;
+    }
+
+    println!("Hint: {hint}.");
+
+    Ok(())
+}
+
+/// Writes the puzzles to stdout in colour, with their solutions (if any) drawn over the blanks.
+fn write_colored(records: &[PuzzleRecord], border: bool, blank_char: &str) -> Result<()> {
+    for (index, record) in records.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+
+        write_grid_colored(
+            record.puzzle,
+            record.solution,
+            &[],
+            None::<PathBuf>,
+            border,
+            blank_char,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Executes the play command.
+fn play_command(input_file: Option<PathBuf>, save_file: Option<PathBuf>) -> Result<()> {
+    let game = match (&input_file, &save_file) {
+        // Playing the input puzzle would overwrite the saved game when quitting.
+        (Some(_), Some(save_file)) if save_file.exists() => {
+            return Err(anyhow!(
+                "Invalid input: {} already has a saved game, omit --input to resume it.",
+                save_file.display()
+            ));
+        }
+        (None, Some(save_file)) if save_file.exists() => read_game(Some(save_file))?,
+        (Some(input_file), _) => Game::new(read_to_grid(Some(input_file))?)?,
+        (None, _) => Game::new(generate())?,
+    };
+
+    let game = play::play(game)?;
+
+    if game.is_solved() {
+        println!(
+            "Solved in {}, with {} mistakes.",
+            play::format_duration(game.elapsed()),
+            game.mistakes()
+        );
+    }
+    if let Some(save_file) = save_file {
+        write_game(&game, Some(save_file))?;
+    }
+
+    Ok(())
+}
+
+/// Executes the book command.
+fn book_command(
+    output_file: Option<PathBuf>,
+    count: usize,
+    per_page: usize,
+    difficulty: BookDifficulty,
+    timeout: Duration,
 ) -> Result<()> {
-    let puzzle = read_to_grid(input_file)?;
-    let solution = solve_any(puzzle).ok_or(anyhow!("No solution."))?;
+    let records = (0..count)
+        .map(|index| {
+            let difficulty = match difficulty {
+                BookDifficulty::Easy => Difficulty::Easy,
+                BookDifficulty::Medium => Difficulty::Medium,
+                BookDifficulty::Hard => Difficulty::Hard,
+                BookDifficulty::Expert => Difficulty::Expert,
+                BookDifficulty::Mixed => Difficulty::ALL[index * Difficulty::ALL.len() / count],
+            };
+
+            let puzzle = generate_with_difficulty_and_budget(
+                difficulty,
+                &mut Rng::new(),
+                &Budget::with_timeout(timeout),
+            )
+            .map_err(|Aborted| timed_out(Some(timeout)))?;
+
+            Ok(PuzzleRecord {
+                puzzle,
+                solution: solve_any(puzzle),
+                metadata: Metadata {
+                    difficulty: Some(difficulty.to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-    write_grid(solution, output_file, !plain_output, " ")
+    write_book(&records, per_page, output_file)
 }
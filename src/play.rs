@@ -0,0 +1,337 @@
+//! The interactive terminal game of the play command.
+
+use std::{
+    io::{Write, stdout},
+    time::Duration,
+};
+
+use anyhow::Result;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{
+        Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+    },
+    terminal::{self, ClearType},
+};
+
+use sudoku::prelude::*;
+
+/// The keys of the game, shown below the grid.
+const HELP: &str =
+    "arrows/hjkl move  1-9 enter  0/del clear  p pencil  u undo  r redo  ? hint  q quit";
+
+/// What the player asked for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Move(isize, isize),
+    Enter(u8),
+    Clear,
+    TogglePencil,
+    Undo,
+    Redo,
+    Hint,
+    Quit,
+}
+
+/// The state of the screen, around the [Game] being played.
+struct Screen {
+    game: Game,
+    cursor: GridPos,
+    pencil: bool,
+    highlights: Vec<GridPos>,
+    message: String,
+}
+
+impl Screen {
+    fn new(game: Game) -> Self {
+        Self {
+            game,
+            cursor: (0, 0),
+            pencil: false,
+            highlights: Vec::new(),
+            message: String::new(),
+        }
+    }
+
+    /// Applies an action, and returns false if the game should end.
+    fn apply(&mut self, action: Action) -> bool {
+        self.message.clear();
+        self.highlights.clear();
+
+        let (row, col) = self.cursor;
+        let played = match action {
+            Action::Move(rows, cols) => {
+                self.cursor = (
+                    (row as isize + rows).rem_euclid(9) as usize,
+                    (col as isize + cols).rem_euclid(9) as usize,
+                );
+                return true;
+            }
+            Action::Enter(digit) if self.pencil => Move::Mark {
+                pos: self.cursor,
+                digit,
+            },
+            Action::Enter(digit) => Move::Enter {
+                pos: self.cursor,
+                digit,
+            },
+            Action::Clear => Move::Clear { pos: self.cursor },
+            Action::Undo => Move::Undo,
+            Action::Redo => Move::Redo,
+            Action::TogglePencil => {
+                self.pencil = !self.pencil;
+                return true;
+            }
+            Action::Hint => {
+                self.hint();
+                return true;
+            }
+            Action::Quit => return false,
+        };
+
+        if let Err(error) = self.game.play(played) {
+            self.message = error.to_string();
+        } else if self.game.is_solved() {
+            self.message = format!("Solved in {}!", format_duration(self.game.elapsed()));
+        } else {
+            // Undoing the last digit of a solved game starts the clock again.
+            self.game.resume();
+            if self.game.board().cells().all(|digit| digit != 0) {
+                self.message = "The grid is full, but not solved yet.".to_string();
+            }
+        }
+
+        true
+    }
+
+    /// Points out the first mistake if there is one, otherwise the next step and the cells it
+    /// depends on.
+    fn hint(&mut self) {
+        let (board, solution) = (self.game.board(), self.game.solution());
+        let mistake = (0..81)
+            .map(|index| (index / 9, index % 9))
+            .find(|&(row, col)| board[row][col] != 0 && board[row][col] != solution[row][col]);
+
+        if let Some((row, col)) = mistake {
+            self.highlights = vec![(row, col)];
+            self.message = format!("There is a mistake at row {}, col {}.", row + 1, col + 1);
+            return;
+        }
+
+        match hint(board) {
+            Some(hint) => {
+                self.highlights = hint.cells.clone();
+                self.highlights.push(hint.pos);
+                self.message = format!("Hint: {hint}.");
+            }
+            None => self.message = "No hint, the next step is too hard.".to_string(),
+        }
+    }
+}
+
+/// Restores the terminal when the game ends, even on errors.
+struct Terminal;
+
+impl Terminal {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+
+        Ok(Self)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Plays a game in the terminal, until it is solved or the player quits, and returns it.
+pub fn play(game: Game) -> Result<Game> {
+    let mut screen = Screen::new(game);
+    let terminal = Terminal::enter()?;
+
+    loop {
+        draw(&screen)?;
+
+        // Wake up every second to keep the timer going.
+        if !event::poll(Duration::from_secs(1))? {
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()?
+            && let Some(action) = key_to_action(key)
+            && !screen.apply(action)
+        {
+            break;
+        }
+    }
+
+    drop(terminal);
+    Ok(screen.game)
+}
+
+/// Maps a key press to its action, if any.
+fn key_to_action(key: KeyEvent) -> Option<Action> {
+    if key.kind == KeyEventKind::Release {
+        return None;
+    }
+
+    Some(match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Up | KeyCode::Char('k') => Action::Move(-1, 0),
+        KeyCode::Down | KeyCode::Char('j') => Action::Move(1, 0),
+        KeyCode::Left | KeyCode::Char('h') => Action::Move(0, -1),
+        KeyCode::Right | KeyCode::Char('l') => Action::Move(0, 1),
+        KeyCode::Char(c @ '1'..='9') => Action::Enter(c as u8 - b'0'),
+        KeyCode::Char('0' | '.' | ' ') | KeyCode::Backspace | KeyCode::Delete => Action::Clear,
+        KeyCode::Char('p') => Action::TogglePencil,
+        KeyCode::Char('u' | 'z') => Action::Undo,
+        KeyCode::Char('r' | 'y') => Action::Redo,
+        KeyCode::Char('?') => Action::Hint,
+        KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+        _ => return None,
+    })
+}
+
+/// Draws the whole screen.
+fn draw(screen: &Screen) -> Result<()> {
+    let game = &screen.game;
+    let mut out = stdout().lock();
+    queue!(
+        out,
+        terminal::Clear(ClearType::All),
+        cursor::MoveTo(0, 0),
+        Print(format!(
+            "Sudoku  {}  Mistakes: {}  Mode: {}",
+            format_duration(game.elapsed()),
+            game.mistakes(),
+            if screen.pencil { "pencil" } else { "digits" }
+        )),
+        cursor::MoveTo(0, 2),
+        Print("╔═════════╤═════════╤═════════╗"),
+    )?;
+
+    let mut line = 3;
+    for row in 0..9 {
+        if row == 3 || row == 6 {
+            queue!(
+                out,
+                cursor::MoveTo(0, line),
+                Print("╟─────────┼─────────┼─────────╢")
+            )?;
+            line += 1;
+        }
+
+        queue!(out, cursor::MoveTo(0, line), Print("║"))?;
+        for col in 0..9 {
+            draw_cell(&mut out, screen, (row, col))?;
+            queue!(out, Print(if col % 3 == 2 && col != 8 { "│" } else { "" }))?;
+        }
+        queue!(out, Print("║"))?;
+        line += 1;
+    }
+
+    let (row, col) = screen.cursor;
+    let marks = (1..=9)
+        .filter(|digit| game.pencil_marks()[row][col] & (1 << digit) != 0)
+        .map(|digit| digit.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    queue!(
+        out,
+        cursor::MoveTo(0, line),
+        Print("╚═════════╧═════════╧═════════╝"),
+        cursor::MoveTo(0, line + 1),
+        Print(format!("Marks: {marks}")),
+        cursor::MoveTo(0, line + 2),
+        Print(&screen.message),
+        cursor::MoveTo(0, line + 4),
+        SetAttribute(Attribute::Dim),
+        Print(HELP),
+        SetAttribute(Attribute::Reset),
+    )?;
+
+    out.flush()?;
+    Ok(())
+}
+
+/// Draws a cell, 3 chars wide. Cells with pencil marks but no digit are drawn as a dot.
+fn draw_cell(out: &mut impl Write, screen: &Screen, pos: GridPos) -> Result<()> {
+    let board = screen.game.board();
+    let digit = board[pos.0][pos.1];
+    let text = match (digit, screen.game.pencil_marks()[pos.0][pos.1]) {
+        (0, 0) => "   ".to_string(),
+        (0, _) => " · ".to_string(),
+        (digit, _) => format!(" {digit} "),
+    };
+
+    if pos == screen.cursor {
+        queue!(out, SetAttribute(Attribute::Reverse))?;
+    }
+    if screen.highlights.contains(&pos) {
+        queue!(out, SetBackgroundColor(Color::DarkYellow))?;
+    }
+
+    if has_conflict(&board, pos) {
+        queue!(out, SetForegroundColor(Color::Red))?;
+    } else if screen.game.puzzle()[pos.0][pos.1] != 0 {
+        queue!(out, SetAttribute(Attribute::Bold))?;
+    } else {
+        queue!(out, SetForegroundColor(Color::Cyan))?;
+    }
+
+    queue!(out, Print(text), SetAttribute(Attribute::Reset), ResetColor)?;
+    Ok(())
+}
+
+/// Formats a duration as minutes and seconds, e.g. 03:21.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_screen() {
+        let solution = solve_any(Grid::EMPTY).unwrap();
+        let mut puzzle = solution;
+        puzzle.set((0, 0), 0);
+        puzzle.set((0, 1), 0);
+
+        let mut screen = Screen::new(Game::new(puzzle).unwrap());
+
+        screen.apply(Action::Enter(solution[0][1]));
+        screen.apply(Action::TogglePencil);
+        screen.apply(Action::Enter(4));
+        assert_eq!(screen.game.pencil_marks()[0][0], 1 << 4);
+        assert_eq!(screen.game.mistakes(), 1);
+
+        screen.apply(Action::Hint);
+        assert_eq!(screen.highlights, vec![(0, 0)]);
+        assert_eq!(screen.message, "There is a mistake at row 1, col 1.");
+
+        // Givens cannot be changed.
+        screen.apply(Action::Move(-1, 3));
+        assert_eq!(screen.cursor, (8, 3));
+        screen.apply(Action::Clear);
+        assert_eq!(screen.message, "Invalid input: givens cannot be changed.");
+
+        screen.cursor = (0, 0);
+        screen.apply(Action::TogglePencil);
+        screen.apply(Action::Enter(solution[0][0]));
+        screen.apply(Action::Move(0, 1));
+        screen.apply(Action::Enter(solution[0][1]));
+        assert!(screen.game.is_solved());
+        assert!(screen.message.starts_with("Solved in "));
+        assert!(!screen.apply(Action::Quit));
+    }
+}
@@ -0,0 +1,278 @@
+//! The `sudoku` Python module, built with the `python` feature.
+//!
+//! A grid can be given as an 81-char string (see [Grid]'s `FromStr`), a flat sequence of 81 ints,
+//! or 9 rows of 9 ints, which includes NumPy arrays of either shape. Grids are returned as 9 lists
+//! of 9 ints, with 0 for blanks, so `numpy.array(grid)` gives a 9x9 array.
+//!
+//! Solving, counting, rating and generating release the GIL while they run.
+
+use std::{ffi::CString, path::PathBuf, str::FromStr, time::Duration};
+
+use fastrand::Rng;
+use pyo3::{
+    exceptions::{PyOSError, PyTimeoutError, PyUserWarning, PyValueError},
+    prelude::*,
+    types::{PyDict, PyString},
+};
+
+use crate::{
+    io::{FileFormat, PuzzleRecord, read_puzzles as read_puzzle_records, read_to_grid_strict},
+    rating::{Difficulty, rate as rate_puzzle},
+    sudoku::{
+        Aborted, Budget, Grid, count_solutions_with_budget, generate_with_budget,
+        generate_with_difficulty_and_budget, solve_any_with_budget, solve_with_budget,
+    },
+};
+
+/// Generates a puzzle with a unique solution, of any difficulty if difficulty is None.
+///
+/// The same seed always gives the same puzzle, for the same version of the library. Raises
+/// TimeoutError if it takes longer than timeout seconds.
+#[pyfunction]
+#[pyo3(signature = (difficulty = None, seed = None, timeout = 10.0))]
+fn generate(
+    py: Python<'_>,
+    difficulty: Option<&str>,
+    seed: Option<u64>,
+    timeout: f64,
+) -> PyResult<Vec<Vec<u32>>> {
+    let difficulty = difficulty
+        .map(Difficulty::from_str)
+        .transpose()
+        .map_err(|error| to_py_err(error.into()))?;
+    let mut rng = seed.map_or_else(Rng::new, Rng::with_seed);
+    let budget = to_budget(Some(timeout))?;
+
+    let puzzle = py
+        .detach(|| match difficulty {
+            Some(difficulty) => generate_with_difficulty_and_budget(difficulty, &mut rng, &budget),
+            None => generate_with_budget(&mut rng, &budget),
+        })
+        .map_err(|Aborted| timed_out(Some(timeout)))?;
+    Ok(grid_to_lists(&puzzle))
+}
+
+/// Finds all solutions to puzzle, which can be slow for puzzles with few givens.
+///
+/// Raises TimeoutError if it takes longer than timeout seconds, or never if timeout is None.
+#[pyfunction]
+#[pyo3(signature = (puzzle, timeout = None))]
+fn solve(
+    py: Python<'_>,
+    puzzle: &Bound<'_, PyAny>,
+    timeout: Option<f64>,
+) -> PyResult<Vec<Vec<Vec<u32>>>> {
+    let puzzle = to_grid(puzzle)?;
+    let budget = to_budget(timeout)?;
+
+    Ok(py
+        .detach(|| solve_with_budget(puzzle, &[], &budget))
+        .map_err(|Aborted| timed_out(timeout))?
+        .iter()
+        .map(grid_to_lists)
+        .collect())
+}
+
+/// Finds any solution to puzzle, or None if it has none.
+///
+/// Raises TimeoutError if it takes longer than timeout seconds, or never if timeout is None.
+#[pyfunction]
+#[pyo3(signature = (puzzle, timeout = None))]
+fn solve_any(
+    py: Python<'_>,
+    puzzle: &Bound<'_, PyAny>,
+    timeout: Option<f64>,
+) -> PyResult<Option<Vec<Vec<u32>>>> {
+    let puzzle = to_grid(puzzle)?;
+    let budget = to_budget(timeout)?;
+
+    Ok(py
+        .detach(|| solve_any_with_budget(puzzle, &[], &budget))
+        .map_err(|Aborted| timed_out(timeout))?
+        .as_ref()
+        .map(grid_to_lists))
+}
+
+/// Counts the solutions to puzzle, stopping at limit.
+///
+/// Raises TimeoutError if it takes longer than timeout seconds, or never if timeout is None.
+#[pyfunction]
+#[pyo3(signature = (puzzle, limit = 1000, timeout = None))]
+fn count_solutions(
+    py: Python<'_>,
+    puzzle: &Bound<'_, PyAny>,
+    limit: usize,
+    timeout: Option<f64>,
+) -> PyResult<usize> {
+    let puzzle = to_grid(puzzle)?;
+    let budget = to_budget(timeout)?;
+
+    py.detach(|| count_solutions_with_budget(puzzle, &[], limit, &budget))
+        .map_err(|Aborted| timed_out(timeout))
+}
+
+/// Rates puzzle as `"Easy"`, `"Medium"`, `"Hard"` or `"Expert"`.
+#[pyfunction]
+fn rate(py: Python<'_>, puzzle: &Bound<'_, PyAny>) -> PyResult<String> {
+    let puzzle = to_grid(puzzle)?;
+
+    Ok(py.detach(|| rate_puzzle(puzzle)).to_string())
+}
+
+/// Reads a grid from a file, in any of the layouts accepted by `read_to_grid`.
+///
+/// Suspicious input is reported as a `UserWarning`, or an error if strict is true.
+#[pyfunction]
+#[pyo3(signature = (path, strict = false))]
+fn read_grid(py: Python<'_>, path: PathBuf, strict: bool) -> PyResult<Vec<Vec<u32>>> {
+    let parsed = read_to_grid_strict(Some(path), strict).map_err(to_py_err)?;
+    for warning in &parsed.warnings {
+        warn(py, &warning.to_string())?;
+    }
+
+    Ok(grid_to_lists(&parsed.value))
+}
+
+/// Reads every puzzle in a file as a dict, with the keys `puzzle`, `solution`, `constraints` and
+/// `metadata`.
+///
+/// format is one of the formats of the `-F` option, or None to guess it from the path and content.
+/// Constraints are dicts in the form of the variant format. Suspicious input is reported as a
+/// `UserWarning`, or an error if strict is true.
+#[pyfunction]
+#[pyo3(signature = (path, format = None, strict = false))]
+fn read_puzzles<'py>(
+    py: Python<'py>,
+    path: PathBuf,
+    format: Option<&str>,
+    strict: bool,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let format = format
+        .map(FileFormat::from_str)
+        .transpose()
+        .map_err(to_py_err)?;
+    let parsed = read_puzzle_records(Some(path), format, strict).map_err(to_py_err)?;
+    for warning in &parsed.warnings {
+        warn(py, &warning.to_string())?;
+    }
+
+    parsed
+        .value
+        .iter()
+        .map(|record| record_to_dict(py, record))
+        .collect()
+}
+
+/// The `sudoku` Python module.
+#[pymodule]
+#[pyo3(name = "sudoku")]
+fn sudoku_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(generate, m)?)?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(solve_any, m)?)?;
+    m.add_function(wrap_pyfunction!(count_solutions, m)?)?;
+    m.add_function(wrap_pyfunction!(rate, m)?)?;
+    m.add_function(wrap_pyfunction!(read_grid, m)?)?;
+    m.add_function(wrap_pyfunction!(read_puzzles, m)?)?;
+
+    Ok(())
+}
+
+/// Converts a Python grid into a [Grid].
+fn to_grid(grid: &Bound<'_, PyAny>) -> PyResult<Grid> {
+    if let Ok(text) = grid.cast::<PyString>() {
+        return Grid::from_str(text.to_str()?).map_err(|error| to_py_err(error.into()));
+    }
+
+    let cells = match grid.extract::<Vec<Vec<u8>>>() {
+        Ok(rows) if rows.len() == 9 && rows.iter().all(|row| row.len() == 9) => rows.concat(),
+        Ok(_) => {
+            return Err(PyValueError::new_err(
+                "Invalid input: grid must have 9 rows of 9.",
+            ));
+        }
+        Err(_) => grid.extract::<Vec<u8>>().map_err(|_| {
+            PyValueError::new_err("Invalid input: grid must be a string or a sequence of ints.")
+        })?,
+    };
+    if cells.len() != 81 {
+        return Err(PyValueError::new_err(
+            "Invalid input: grid must have exactly 81 cells.",
+        ));
+    }
+
+    Grid::try_from(std::array::from_fn(|row| {
+        std::array::from_fn(|col| cells[row * 9 + col])
+    }))
+    .map_err(|error| to_py_err(error.into()))
+}
+
+/// Converts a timeout in seconds into a [Budget], which has no limit if timeout is None.
+fn to_budget(timeout: Option<f64>) -> PyResult<Budget> {
+    timeout.map_or(Ok(Budget::default()), |timeout| {
+        Duration::try_from_secs_f64(timeout)
+            .map(Budget::with_timeout)
+            .map_err(|error| PyValueError::new_err(error.to_string()))
+    })
+}
+
+/// The error raised when a search runs out of its [Budget].
+fn timed_out(timeout: Option<f64>) -> PyErr {
+    PyTimeoutError::new_err(format!(
+        "Timed out after {} s.",
+        timeout.unwrap_or_default()
+    ))
+}
+
+/// Converts a [Grid] into 9 rows of 9 ints, as u32 since rows of u8 would become bytes.
+fn grid_to_lists(grid: &Grid) -> Vec<Vec<u32>> {
+    grid.rows()
+        .map(|row| row.into_iter().map(u32::from).collect())
+        .collect()
+}
+
+/// Converts a [PuzzleRecord] into a dict.
+fn record_to_dict<'py>(py: Python<'py>, record: &PuzzleRecord) -> PyResult<Bound<'py, PyDict>> {
+    let constraints = serde_json::to_string(&record.constraints)
+        .map_err(|error| PyValueError::new_err(error.to_string()))?;
+    let metadata = &record.metadata;
+
+    let metadata_dict = PyDict::new(py);
+    for (key, value) in [
+        ("author", &metadata.author),
+        ("description", &metadata.description),
+        ("comment", &metadata.comment),
+        ("date", &metadata.date),
+        ("source", &metadata.source),
+        ("source_url", &metadata.source_url),
+        ("difficulty", &metadata.difficulty),
+    ] {
+        metadata_dict.set_item(key, value)?;
+    }
+
+    let dict = PyDict::new(py);
+    dict.set_item("puzzle", grid_to_lists(&record.puzzle))?;
+    dict.set_item("solution", record.solution.as_ref().map(grid_to_lists))?;
+    dict.set_item(
+        "constraints",
+        py.import("json")?.call_method1("loads", (constraints,))?,
+    )?;
+    dict.set_item("metadata", metadata_dict)?;
+
+    Ok(dict)
+}
+
+/// Reports a `UserWarning`.
+fn warn(py: Python<'_>, message: &str) -> PyResult<()> {
+    let message =
+        CString::new(message).map_err(|error| PyValueError::new_err(error.to_string()))?;
+    PyErr::warn(py, &py.get_type::<PyUserWarning>(), &message, 1)
+}
+
+/// Converts an error into an `OSError` if it came from reading a file, or else a `ValueError`.
+fn to_py_err(error: anyhow::Error) -> PyErr {
+    match error.downcast::<std::io::Error>() {
+        Ok(error) => PyOSError::new_err(error.to_string()),
+        Err(error) => PyValueError::new_err(error.to_string()),
+    }
+}
@@ -0,0 +1,489 @@
+use alloc::vec::Vec;
+use core::{
+    array,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::sudoku::{GridPos, InputError, PencilMarks, Puzzle, is_valid_puzzle};
+
+/// How hard a puzzle is for a human, by the hardest technique needed to solve it without guessing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Difficulty {
+    /// Naked and hidden singles only.
+    Easy,
+
+    /// Also locked candidates (pointing and claiming).
+    Medium,
+
+    /// Also naked and hidden pairs.
+    Hard,
+
+    /// Needs more than the above, e.g. guessing.
+    Expert,
+}
+
+impl Difficulty {
+    /// All the difficulties, from the easiest.
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+        })
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, InputError> {
+        match s.to_ascii_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            "expert" => Ok(Difficulty::Expert),
+            _ => Err(InputError::UnknownDifficulty(s.into())),
+        }
+    }
+}
+
+/// A logical technique to make progress on a puzzle, from the simplest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Technique {
+    /// A cell has only one candidate.
+    NakedSingle,
+
+    /// A digit has only one possible cell in a row, col or square.
+    HiddenSingle,
+
+    /// The candidates for a digit in a unit all lie in the intersection with another unit, so it
+    /// can be removed from the rest of that other unit.
+    LockedCandidates,
+
+    /// Two cells in a unit have the same two candidates, so they can be removed from the rest of the
+    /// unit.
+    NakedPair,
+
+    /// Two digits in a unit can only go in the same two cells, so the other candidates can be
+    /// removed from those cells.
+    HiddenPair,
+}
+
+impl Technique {
+    /// The [Difficulty] of puzzles that need the technique.
+    pub fn difficulty(self) -> Difficulty {
+        match self {
+            Technique::NakedSingle | Technique::HiddenSingle => Difficulty::Easy,
+            Technique::LockedCandidates => Difficulty::Medium,
+            Technique::NakedPair | Technique::HiddenPair => Difficulty::Hard,
+        }
+    }
+}
+
+impl Display for Technique {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Technique::NakedSingle => "naked single",
+            Technique::HiddenSingle => "hidden single",
+            Technique::LockedCandidates => "locked candidates",
+            Technique::NakedPair => "naked pair",
+            Technique::HiddenPair => "hidden pair",
+        })
+    }
+}
+
+/// A digit that can be filled in by logic, see [hint].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hint {
+    pub pos: GridPos,
+    pub digit: u8,
+
+    /// The hardest technique needed to find the digit.
+    pub technique: Technique,
+
+    /// The cells that show why the digit goes there, i.e. the filled peers of a naked single, or the
+    /// unit of a hidden single.
+    pub cells: Vec<GridPos>,
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at row {}, col {} ({})",
+            self.digit,
+            self.pos.0 + 1,
+            self.pos.1 + 1,
+            self.technique
+        )
+    }
+}
+
+/// Rates how hard a puzzle is for a human, see [Difficulty].
+///
+/// Puzzles that are invalid, or that do not have an unique solution, are rated as
+/// [Difficulty::Expert], as they cannot be solved by logic alone.
+pub fn rate(puzzle: Puzzle) -> Difficulty {
+    if !is_valid_puzzle(&puzzle) {
+        return Difficulty::Expert;
+    }
+
+    let mut state = State::new(puzzle);
+    let mut difficulty = Difficulty::Easy;
+
+    while !state.is_solved() {
+        match state.next_hint() {
+            Some(hint) => {
+                difficulty = difficulty.max(hint.technique.difficulty());
+                state.place(hint.pos, hint.digit);
+            }
+            None => return Difficulty::Expert,
+        }
+    }
+
+    difficulty
+}
+
+/// Finds the next digit that can be filled in by logic, along with how it was found.
+///
+/// Returns None if the puzzle is invalid, already solved, or needs more than the techniques in
+/// [Technique], e.g. guessing.
+pub fn hint(puzzle: Puzzle) -> Option<Hint> {
+    if !is_valid_puzzle(&puzzle) {
+        return None;
+    }
+
+    State::new(puzzle).next_hint()
+}
+
+/// The 27 units (rows, cols and squares), each of which must hold 1-9 exactly once.
+fn units() -> [[GridPos; 9]; 27] {
+    array::from_fn(|index| {
+        let (kind, index) = (index / 9, index % 9);
+
+        array::from_fn(|offset| match kind {
+            0 => (index, offset),
+            1 => (offset, index),
+            _ => (index / 3 * 3 + offset / 3, index % 3 * 3 + offset % 3),
+        })
+    })
+}
+
+/// A puzzle being solved by logic, along with the candidates of its blank cells.
+struct State {
+    puzzle: Puzzle,
+    candidates: PencilMarks,
+    units: [[GridPos; 9]; 27],
+}
+
+impl State {
+    fn new(puzzle: Puzzle) -> Self {
+        let mut state = Self {
+            puzzle,
+            candidates: [[0b11_1111_1110; 9]; 9],
+            units: units(),
+        };
+
+        for (row, digits) in puzzle.rows().enumerate() {
+            for (col, digit) in digits.into_iter().enumerate() {
+                if digit != 0 {
+                    state.place((row, col), digit);
+                }
+            }
+        }
+
+        state
+    }
+
+    fn is_solved(&self) -> bool {
+        self.puzzle.cells().all(|digit| digit != 0)
+    }
+
+    /// Verifies whether a blank cell has no candidates left.
+    fn has_contradiction(&self) -> bool {
+        (0..9).any(|row| {
+            (0..9).any(|col| self.puzzle[row][col] == 0 && self.candidates[row][col] == 0)
+        })
+    }
+
+    /// Fills in a cell, and removes the digit from the candidates of its peers.
+    fn place(&mut self, (row, col): GridPos, digit: u8) {
+        self.puzzle.set((row, col), digit);
+        self.candidates[row][col] = 0;
+
+        for unit in self.units {
+            if unit.contains(&(row, col)) {
+                for (peer_row, peer_col) in unit {
+                    self.candidates[peer_row][peer_col] &= !(1 << digit);
+                }
+            }
+        }
+    }
+
+    /// Gets the cells of a unit that have digit as a candidate.
+    fn cells_with(&self, unit: [GridPos; 9], digit: u8) -> Vec<GridPos> {
+        unit.into_iter()
+            .filter(|(row, col)| self.candidates[*row][*col] & (1 << digit) != 0)
+            .collect()
+    }
+
+    /// Removes candidates from a cell. Returns true if any was removed.
+    fn eliminate(&mut self, (row, col): GridPos, mask: u16) -> bool {
+        let before = self.candidates[row][col];
+        self.candidates[row][col] &= !mask;
+
+        before != self.candidates[row][col]
+    }
+
+    /// Finds the next digit to fill in, removing candidates with harder techniques until a single
+    /// is found.
+    fn next_hint(&mut self) -> Option<Hint> {
+        let mut technique = Technique::NakedSingle;
+
+        loop {
+            if self.is_solved() || self.has_contradiction() {
+                return None;
+            }
+
+            if let Some(mut hint) = self.naked_single().or_else(|| self.hidden_single()) {
+                hint.technique = hint.technique.max(technique);
+                return Some(hint);
+            }
+
+            technique = technique.max(if self.locked_candidates() {
+                Technique::LockedCandidates
+            } else if self.naked_pair() {
+                Technique::NakedPair
+            } else if self.hidden_pair() {
+                Technique::HiddenPair
+            } else {
+                return None;
+            });
+        }
+    }
+
+    /// Finds a cell that has only one candidate.
+    fn naked_single(&self) -> Option<Hint> {
+        let (row, col) = (0..81)
+            .map(|index| (index / 9, index % 9))
+            .find(|(row, col)| self.candidates[*row][*col].count_ones() == 1)?;
+
+        let cells = self
+            .units
+            .iter()
+            .filter(|unit| unit.contains(&(row, col)))
+            .flatten()
+            .filter(|(peer_row, peer_col)| self.puzzle[*peer_row][*peer_col] != 0)
+            .copied()
+            .collect::<Vec<_>>();
+
+        Some(Hint {
+            pos: (row, col),
+            digit: self.candidates[row][col].trailing_zeros() as u8,
+            technique: Technique::NakedSingle,
+            cells: sorted(cells),
+        })
+    }
+
+    /// Finds a digit that has only one possible cell in a unit.
+    fn hidden_single(&self) -> Option<Hint> {
+        for unit in self.units {
+            for digit in 1..=9 {
+                if let [pos] = self.cells_with(unit, digit)[..] {
+                    return Some(Hint {
+                        pos,
+                        digit,
+                        technique: Technique::HiddenSingle,
+                        cells: sorted(unit.to_vec()),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// When the candidates for a digit in a unit all lie in the intersection with another unit,
+    /// removes the digit from the rest of that other unit.
+    fn locked_candidates(&mut self) -> bool {
+        for unit in self.units {
+            for digit in 1..=9 {
+                let cells = self.cells_with(unit, digit);
+                if cells.is_empty() {
+                    continue;
+                }
+
+                for other in self.units {
+                    if other == unit || !cells.iter().all(|pos| other.contains(pos)) {
+                        continue;
+                    }
+
+                    let mut progress = false;
+                    for pos in other.into_iter().filter(|pos| !unit.contains(pos)) {
+                        progress |= self.eliminate(pos, 1 << digit);
+                    }
+                    if progress {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// When two cells in a unit have the same two candidates, removes them from the rest of the
+    /// unit.
+    fn naked_pair(&mut self) -> bool {
+        for unit in self.units {
+            for (index, (row_a, col_a)) in unit.into_iter().enumerate() {
+                let mask = self.candidates[row_a][col_a];
+                if mask.count_ones() != 2 {
+                    continue;
+                }
+
+                for (row_b, col_b) in unit.into_iter().skip(index + 1) {
+                    if self.candidates[row_b][col_b] != mask {
+                        continue;
+                    }
+
+                    let mut progress = false;
+                    for pos in unit {
+                        if pos != (row_a, col_a) && pos != (row_b, col_b) {
+                            progress |= self.eliminate(pos, mask);
+                        }
+                    }
+                    if progress {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// When two digits in a unit can only go in the same two cells, removes the other candidates
+    /// from those cells.
+    fn hidden_pair(&mut self) -> bool {
+        for unit in self.units {
+            for digit_a in 1..=9 {
+                let cells = self.cells_with(unit, digit_a);
+                if cells.len() != 2 {
+                    continue;
+                }
+
+                for digit_b in digit_a + 1..=9 {
+                    if self.cells_with(unit, digit_b) != cells {
+                        continue;
+                    }
+
+                    let mask = !(1 << digit_a | 1 << digit_b);
+                    let mut progress = false;
+                    for pos in &cells {
+                        progress |= self.eliminate(*pos, mask);
+                    }
+                    if progress {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// Sorts cells into reading order, without duplicates.
+fn sorted(mut cells: Vec<GridPos>) -> Vec<GridPos> {
+    cells.sort();
+    cells.dedup();
+
+    cells
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::sudoku::Grid;
+
+    #[test]
+    fn check_rate() {
+        let easy = Grid::new([
+            [5, 3, 0, 0, 7, 0, 0, 0, 0],
+            [6, 0, 0, 1, 9, 5, 0, 0, 0],
+            [0, 9, 8, 0, 0, 0, 0, 6, 0],
+            [8, 0, 0, 0, 6, 0, 0, 0, 3],
+            [4, 0, 0, 8, 0, 3, 0, 0, 1],
+            [7, 0, 0, 0, 2, 0, 0, 0, 6],
+            [0, 6, 0, 0, 0, 0, 2, 8, 0],
+            [0, 0, 0, 4, 1, 9, 0, 0, 5],
+            [0, 0, 0, 0, 8, 0, 0, 7, 9],
+        ])
+        .unwrap();
+        assert_eq!(rate(easy), Difficulty::Easy);
+
+        // Arto Inkala's "world's hardest sudoku".
+        let expert = Grid::new([
+            [8, 0, 0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 3, 6, 0, 0, 0, 0, 0],
+            [0, 7, 0, 0, 9, 0, 2, 0, 0],
+            [0, 5, 0, 0, 0, 7, 0, 0, 0],
+            [0, 0, 0, 0, 4, 5, 7, 0, 0],
+            [0, 0, 0, 1, 0, 0, 0, 3, 0],
+            [0, 0, 1, 0, 0, 0, 0, 6, 8],
+            [0, 0, 8, 5, 0, 0, 0, 1, 0],
+            [0, 9, 0, 0, 0, 0, 4, 0, 0],
+        ])
+        .unwrap();
+        assert_eq!(rate(expert), Difficulty::Expert);
+        assert_eq!(hint(expert), None);
+
+        // Too many solutions to tell apart by logic.
+        assert_eq!(rate(Grid::EMPTY), Difficulty::Expert);
+
+        assert_eq!(
+            hint(easy),
+            Some(Hint {
+                pos: (4, 4),
+                digit: 5,
+                technique: Technique::NakedSingle,
+                cells: vec![
+                    (0, 4),
+                    (1, 4),
+                    (3, 4),
+                    (4, 0),
+                    (4, 3),
+                    (4, 5),
+                    (4, 8),
+                    (5, 4),
+                    (7, 4),
+                    (8, 4)
+                ],
+            })
+        );
+
+        assert_eq!("hard".parse::<Difficulty>().unwrap(), Difficulty::Hard);
+        assert!("impossible".parse::<Difficulty>().is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Compact serde forms for grids.
+//!
+//! A [Grid] is an 81-char string in reading order, with digits 1-9 and `.` for blanks, e.g.
+//! `"53..7....6..195...` and so on, see its [FromStr](core::str::FromStr) impl. [PencilMarks] are
+//! a plain array, so their form is in a module to use with `#[serde(with = "...")]`: a list of 81
+//! strings in reading order, each with the candidate digits of a cell in increasing order, e.g.
+//! `["", "19", "3", ...]`.
+//!
+//! Both forms are stable, and the same in JSON, TOML and binary formats.
+
+use alloc::{format, string::String, vec::Vec};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+use crate::sudoku::{Grid, PencilMarks};
+
+impl Serialize for Grid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// The serde form of optional [PencilMarks], where None is left out or null.
+pub mod option_pencil_marks {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        marks: &Option<PencilMarks>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match marks {
+            Some(marks) => serializer.serialize_some(&pencil_marks_to_strings(marks)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<PencilMarks>, D::Error> {
+        Option::<Vec<String>>::deserialize(deserializer)?
+            .map(|marks| strings_to_pencil_marks(&marks).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+/// Converts [PencilMarks] to their serde form.
+fn pencil_marks_to_strings(marks: &PencilMarks) -> Vec<String> {
+    marks
+        .iter()
+        .flatten()
+        .map(|&mask| {
+            (1..=9)
+                .filter(|digit| mask & (1 << digit) != 0)
+                .map(|digit| char::from(b'0' + digit))
+                .collect()
+        })
+        .collect()
+}
+
+/// Converts the serde form of [PencilMarks] back.
+fn strings_to_pencil_marks(cells: &[String]) -> Result<PencilMarks, String> {
+    if cells.len() != 81 {
+        return Err(format!(
+            "pencil marks must have exactly 81 cells, not {}",
+            cells.len()
+        ));
+    }
+
+    let mut marks = [[0; 9]; 9];
+    for (index, cell) in cells.iter().enumerate() {
+        for c in cell.chars() {
+            let digit = c
+                .to_digit(10)
+                .filter(|digit| (1..=9).contains(digit))
+                .ok_or(format!("pencil marks have an unknown char {c:?}"))?;
+            marks[index / 9][index % 9] |= 1 << digit;
+        }
+    }
+
+    Ok(marks)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{
+        io::{Metadata, PuzzleRecord},
+        rating::{Difficulty, hint},
+        sudoku::Grid,
+        variant::Constraint,
+    };
+
+    #[test]
+    fn check_serde() {
+        let mut puzzle = Grid::EMPTY;
+        puzzle.set((0, 0), 5);
+        puzzle.set((8, 8), 9);
+        let mut pencil_marks = [[0; 9]; 9];
+        pencil_marks[0][1] = 1 << 1 | 1 << 9;
+
+        let record = PuzzleRecord {
+            puzzle,
+            constraints: vec![Constraint::X {
+                cells: [(0, 0), (0, 1)],
+            }],
+            metadata: Metadata {
+                author: Some("Jane".into()),
+                ..Default::default()
+            },
+            solution: None,
+            pencil_marks: Some(pencil_marks),
+        };
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.starts_with(&format!(r#"{{"puzzle":"5{}9","#, ".".repeat(79))));
+        assert!(json.contains(r#""pencil_marks":["","19","#));
+        assert_eq!(serde_json::from_str::<PuzzleRecord>(&json).unwrap(), record);
+
+        let json = format!(r#"{{"puzzle": "{}"}}"#, "0".repeat(81));
+        assert_eq!(
+            serde_json::from_str::<PuzzleRecord>(&json).unwrap(),
+            PuzzleRecord::default()
+        );
+        let json = format!(r#"{{"puzzle": "{}x"}}"#, "0".repeat(80));
+        assert!(serde_json::from_str::<PuzzleRecord>(&json).is_err());
+
+        assert_eq!(
+            serde_json::to_string(&Difficulty::Hard).unwrap(),
+            r#""Hard""#
+        );
+        let mut puzzle = crate::sudoku::solve_any(Grid::EMPTY).unwrap();
+        puzzle.set((0, 0), 0);
+        let json = serde_json::to_string(&hint(puzzle)).unwrap();
+        assert!(json.contains(r#""pos":[0,0]"#));
+        assert!(json.contains(r#""technique":"naked_single""#));
+    }
+}
@@ -0,0 +1,344 @@
+//! The HTTP JSON API of the serve command.
+
+use std::{
+    io::Read,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use sudoku::prelude::*;
+
+/// The limits on every request.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    /// The largest request body, in bytes.
+    pub max_body: usize,
+
+    /// The longest time to work on a request, before giving up on it and stopping its search.
+    pub timeout: Duration,
+
+    /// The largest number of solutions to count.
+    pub max_count: usize,
+}
+
+/// The body of every request, where each endpoint only uses the fields it needs.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ApiRequest {
+    /// The 81 cells in reading order, with digits 1-9 for givens and `.` or `0` for blanks.
+    puzzle: Option<String>,
+
+    /// The additional rules of a variant puzzle, see [Constraint].
+    constraints: Vec<Constraint>,
+
+    /// The difficulty of the puzzle to generate.
+    difficulty: Option<String>,
+
+    /// The number of solutions to stop counting at.
+    limit: Option<usize>,
+}
+
+/// A response status and its JSON body.
+type ApiResponse = (u16, Value);
+
+/// Serves the API on address (e.g. `127.0.0.1:8080`), handling up to threads requests at once,
+/// until the process is stopped.
+pub fn serve(address: &str, threads: usize, limits: Limits) -> Result<()> {
+    let server = Arc::new(
+        Server::http(address).map_err(|error| anyhow!("Cannot listen on {address}: {error}."))?,
+    );
+    eprintln!("Listening on http://{address}");
+
+    let workers = (0..threads.max(1))
+        .map(|_| {
+            let server = Arc::clone(&server);
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle(request, &limits);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for worker in workers {
+        let _ = worker.join();
+    }
+
+    Ok(())
+}
+
+/// Reads a request, and writes its response.
+fn handle(mut request: Request, limits: &Limits) {
+    let (status, body) = if request.body_length().unwrap_or(0) > limits.max_body {
+        error(413, "Request body is too large.")
+    } else {
+        let mut body = String::new();
+        match request
+            .as_reader()
+            .take(limits.max_body as u64 + 1)
+            .read_to_string(&mut body)
+        {
+            Ok(_) if body.len() > limits.max_body => error(413, "Request body is too large."),
+            Ok(_) => respond(request.method(), request.url(), &body, limits),
+            Err(_) => error(400, "Request body is not UTF-8."),
+        }
+    };
+
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").expect("valid header"));
+    let _ = request.respond(response);
+}
+
+/// Works out the response to a request for path, which may have a query string that is ignored.
+fn respond(method: &Method, path: &str, body: &str, limits: &Limits) -> ApiResponse {
+    let path = path.split_once('?').map_or(path, |(path, _)| path);
+    let endpoint = match path {
+        "/generate" | "/solve" | "/rate" | "/hint" | "/check" | "/count" => path.to_string(),
+        _ => return error(404, &format!("Unknown endpoint: {path}.")),
+    };
+    if *method != Method::Post {
+        return error(405, "Only POST is supported.");
+    }
+
+    let request: ApiRequest = if body.trim().is_empty() {
+        ApiRequest::default()
+    } else {
+        match serde_json::from_str(body) {
+            Ok(request) => request,
+            Err(error) => {
+                return self::error(400, &format!("Invalid input: bad request: {error}."));
+            }
+        }
+    };
+
+    let limits = *limits;
+    with_timeout(limits.timeout, move |budget| {
+        endpoint_response(&endpoint, request, &limits, budget).unwrap_or_else(|error| {
+            if error.is::<Aborted>() {
+                timed_out(limits.timeout)
+            } else {
+                self::error(400, &error.to_string())
+            }
+        })
+    })
+}
+
+/// Runs an endpoint, with its searches limited by budget.
+fn endpoint_response(
+    endpoint: &str,
+    request: ApiRequest,
+    limits: &Limits,
+    budget: &Budget,
+) -> Result<ApiResponse> {
+    if endpoint == "/generate" {
+        let rng = &mut Rng::new();
+        let puzzle = match &request.difficulty {
+            Some(difficulty) => {
+                generate_with_difficulty_and_budget(Difficulty::from_str(difficulty)?, rng, budget)?
+            }
+            None => generate_with_budget(rng, budget)?,
+        };
+
+        return Ok((
+            200,
+            json!({ "puzzle": puzzle.to_string(), "difficulty": rate(puzzle).to_string() }),
+        ));
+    }
+
+    let puzzle = Grid::from_str(
+        request
+            .puzzle
+            .as_deref()
+            .ok_or(anyhow!("Invalid input: puzzle is missing."))?,
+    )?;
+    if let Some(index) = request
+        .constraints
+        .iter()
+        .position(|constraint| !constraint.is_well_formed())
+    {
+        return Err(anyhow!("Invalid input: clue {} is malformed.", index + 1));
+    }
+    let constraints = &request.constraints;
+    if matches!(endpoint, "/rate" | "/hint") && !constraints.is_empty() {
+        return Err(anyhow!(
+            "Unsupported: {endpoint} cannot use variant constraints."
+        ));
+    }
+
+    let body = match endpoint {
+        "/solve" => match solve_any_with_budget(puzzle, constraints, budget)? {
+            Some(solution) => json!({ "solution": solution.to_string() }),
+            None => return Ok(error(422, "No solution for the puzzle.")),
+        },
+        "/rate" => json!({ "difficulty": rate(puzzle).to_string() }),
+        "/hint" => match hint(puzzle) {
+            Some(hint) => json!({
+                "hint": {
+                    "pos": hint.pos,
+                    "digit": hint.digit,
+                    "technique": hint.technique.to_string(),
+                    "cells": hint.cells,
+                    "text": hint.to_string(),
+                }
+            }),
+            None => json!({ "hint": null }),
+        },
+        "/check" => {
+            let violations = validate(&puzzle, constraints);
+            let conflicts = (0..81)
+                .map(|index| (index / 9, index % 9))
+                .filter(|&pos| has_conflict(&puzzle, pos))
+                .collect::<Vec<_>>();
+
+            json!({
+                "valid": violations.is_empty(),
+                "complete": puzzle.cells().all(|digit| digit != 0),
+                "violations": violations.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                "conflicts": conflicts,
+            })
+        }
+        "/count" => {
+            let limit = request
+                .limit
+                .unwrap_or(limits.max_count)
+                .min(limits.max_count);
+            json!({
+                "count": count_solutions_with_budget(puzzle, constraints, limit, budget)?,
+                "limit": limit,
+            })
+        }
+        _ => unreachable!("checked in respond"),
+    };
+
+    Ok((200, body))
+}
+
+/// Runs work on its own thread with a budget that runs out after timeout, and gives up on it then.
+///
+/// The budget is also cancelled when the work is given up on, so that its searches stop soon after
+/// rather than finishing in the background.
+fn with_timeout(
+    timeout: Duration,
+    work: impl FnOnce(&Budget) -> ApiResponse + Send + 'static,
+) -> ApiResponse {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let budget = Budget {
+        cancel: Some(Arc::clone(&cancel)),
+        ..Budget::with_timeout(timeout)
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(work(&budget));
+    });
+
+    receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+        cancel.store(true, Ordering::Relaxed);
+        timed_out(timeout)
+    })
+}
+
+/// The error response of a request that took longer than timeout.
+fn timed_out(timeout: Duration) -> ApiResponse {
+    error(503, &format!("Timed out after {} ms.", timeout.as_millis()))
+}
+
+/// An error response.
+fn error(status: u16, message: &str) -> ApiResponse {
+    (status, json!({ "error": message }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: Limits = Limits {
+        max_body: 1024,
+        timeout: Duration::from_secs(10),
+        max_count: 5,
+    };
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    fn post(path: &str, body: &str) -> ApiResponse {
+        respond(&Method::Post, path, body, &LIMITS)
+    }
+
+    #[test]
+    fn check_endpoints() {
+        let (status, body) = post("/solve", &format!(r#"{{"puzzle": "{PUZZLE}"}}"#));
+        assert_eq!(status, 200);
+        assert!(body["solution"].as_str().unwrap().starts_with("534678912"));
+
+        let (_, body) = post("/rate", &format!(r#"{{"puzzle": "{PUZZLE}"}}"#));
+        assert_eq!(body["difficulty"], "Easy");
+
+        let (_, body) = post("/hint", &format!(r#"{{"puzzle": "{PUZZLE}"}}"#));
+        assert_eq!(body["hint"]["pos"], json!([4, 4]));
+        assert_eq!(body["hint"]["digit"], 5);
+
+        let (_, body) = post("/check", &format!(r#"{{"puzzle": "55{}"}}"#, &PUZZLE[2..]));
+        assert_eq!(body["valid"], false);
+        assert_eq!(body["conflicts"], json!([[0, 0], [0, 1]]));
+
+        let (_, body) = post("/count", r#"{"puzzle": "", "limit": 100}"#);
+        assert!(body["error"].as_str().unwrap().contains("81 cells"));
+        let (_, body) = post(
+            "/count",
+            &format!(r#"{{"puzzle": "{}", "limit": 100}}"#, ".".repeat(81)),
+        );
+        assert_eq!(body, json!({ "count": 5, "limit": 5 }));
+
+        let (status, _) = post("/solve", &format!(r#"{{"puzzle": "55{}"}}"#, &PUZZLE[2..]));
+        assert_eq!(status, 422);
+        assert_eq!(post("/solve", "{").0, 400);
+        assert_eq!(post("/nope", "").0, 404);
+
+        let (status, body) = post("/count?x=1", &format!(r#"{{"puzzle": "{PUZZLE}"}}"#));
+        assert_eq!((status, body["count"].clone()), (200, json!(1)));
+        let (status, body) = post("/solve", &format!(r#"{{"puzzle": "5l{}"}}"#, &PUZZLE[2..]));
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("'l'"));
+        for path in ["/rate", "/hint"] {
+            let (status, body) = post(
+                path,
+                &format!(
+                    r#"{{"puzzle": "{PUZZLE}", "constraints": [{{"type": "x", "cells": [[0, 2], [0, 3]]}}]}}"#
+                ),
+            );
+            assert_eq!(status, 400, "{path}");
+            assert!(body["error"].as_str().unwrap().contains("variant"));
+        }
+        assert_eq!(respond(&Method::Get, "/solve", "", &LIMITS).0, 405);
+
+        let (status, _) = with_timeout(Duration::ZERO, |_| {
+            thread::sleep(Duration::from_millis(100));
+            (200, Value::Null)
+        });
+        assert_eq!(status, 503);
+
+        let cancelled = Budget {
+            cancel: Some(Arc::new(AtomicBool::new(true))),
+            ..Default::default()
+        };
+        let request = ApiRequest {
+            puzzle: Some(".".repeat(81)),
+            ..Default::default()
+        };
+        let error = endpoint_response("/count", request, &LIMITS, &cancelled).unwrap_err();
+        assert!(error.is::<Aborted>());
+    }
+}
@@ -1,7 +1,42 @@
+//! The grid, validation, solving and generation, which only need `core`, see the crate's features.
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, sync::Arc, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::{
+    array,
+    fmt::{self, Display, Formatter},
+    ops::{Deref, DerefMut, Index},
+    str::FromStr,
+    time::Duration,
+};
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "std")]
+use fastrand::Rng;
+
+#[cfg(feature = "alloc")]
+pub use crate::variant::Constraint;
+#[cfg(feature = "alloc")]
+use crate::{
+    rating::{Difficulty, rate},
+    variant::edge_clues,
+};
+
 /// 9x9 Sudoku grid in reading order.
 ///
-/// Use 1-9 to represent a digit, and 0 to represent a blank or unknown.
-pub type Grid = [[u8; 9]; 9];
+/// Use 1-9 to represent a digit, and 0 to represent a blank or unknown. Every cell is always in
+/// 0..=9, which is checked when a grid is built or changed.
+///
+/// A grid can be indexed by (row, col) for a cell, or by row for a whole row, so that
+/// `grid[row][col]` works as it does for the raw `[[u8; 9]; 9]` array.
+///
+/// Its [Display] and [FromStr] form is 81 chars in reading order, with `.` for blanks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Grid([[u8; 9]; 9]);
+
 pub type Puzzle = Grid;
 pub type Solution = Grid;
 
@@ -17,68 +52,713 @@ const TARGET_BLANKS_TO_GENERATE: usize = 35;
 /// The upper limit of random blanks that can be created.
 const MAX_BLANKS_TO_GENERATE: usize = 64;
 
+/// The candidate digits of every cell of a [Grid], in reading order.
+///
+/// Bit d is set if digit d (1-9) is a candidate.
+pub type PencilMarks = [[u16; 9]; 9];
+
+/// The number of orders to add givens back in, before giving up on a puzzle, see
+/// [generate_with_difficulty_and_budget].
+#[cfg(feature = "alloc")]
+const ATTEMPTS_PER_MINIMAL_PUZZLE: usize = 20;
+
 /// (row, col)
-type GridPos = (usize, usize);
+pub type GridPos = (usize, usize);
 
-/// Finds all solutions to the given puzzle, if any.
-pub fn solve(puzzle: Puzzle) -> Vec<Solution> {
-    if !is_valid_puzzle(&puzzle) {
-        return vec![];
+/// The number of grids a search visits between looking at the clock for its deadline, which is
+/// slower than looking at a node limit or a flag.
+#[cfg(feature = "std")]
+const NODES_PER_DEADLINE_CHECK: u64 = 64;
+
+/// Counts of the work done by the backtracking search of a solve, see [solve_any_with_stats].
+///
+/// They depend only on the puzzle and the search order, not the machine, so they can be compared
+/// across runs, e.g. as a cheap proxy for difficulty or to spot a slower search.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// The cells where more than one digit fit, i.e. where the search had to choose.
+    pub guesses: u64,
+
+    /// The digits that were taken back because they led to a dead end.
+    pub backtracks: u64,
+
+    /// The most blanks filled at once.
+    pub max_depth: usize,
+
+    /// The grids visited, i.e. the puzzle plus every digit that fit.
+    pub nodes: u64,
+
+    /// The digits checked against the rules.
+    pub propagations: u64,
+
+    /// The time taken, which unlike the counts depends on the machine.
+    pub time: Duration,
+}
+
+impl Display for SolveStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} guesses, {} backtracks, max depth {}, {} nodes, {} propagations in {:.3?}",
+            self.guesses, self.backtracks, self.max_depth, self.nodes, self.propagations, self.time
+        )
     }
+}
 
-    let blanks = blanks(puzzle);
-    if blanks.is_empty() {
-        return vec![puzzle];
+/// Limits on the work of a search, after which it gives up with [Aborted].
+///
+/// The default budget is unlimited. Any of the limits can be combined, e.g.:
+/// ```
+/// # use std::{sync::{Arc, atomic::AtomicBool}, time::Duration};
+/// # use sudoku::prelude::*;
+/// let cancel = Arc::new(AtomicBool::new(false));
+/// let budget = Budget {
+///     max_nodes: Some(1_000_000),
+///     cancel: Some(Arc::clone(&cancel)),
+///     ..Budget::with_timeout(Duration::from_secs(1))
+/// };
+/// assert_eq!(count_solutions_with_budget(Grid::EMPTY, &[], usize::MAX, &budget), Err(Aborted));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    /// The most grids to visit, counted as in [SolveStats::nodes] over every search of a call.
+    pub max_nodes: Option<u64>,
+
+    /// The time to give up at.
+    #[cfg(feature = "std")]
+    pub deadline: Option<Instant>,
+
+    /// A flag to give up once it is set, e.g. by another thread or an interrupt.
+    #[cfg(feature = "alloc")]
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Budget {
+    /// Creates a budget that gives up once timeout has passed from now.
+    #[cfg(feature = "std")]
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Budget {
+            deadline: Instant::now().checked_add(timeout),
+            ..Default::default()
+        }
     }
 
-    let mut solutions = Vec::new();
-    find_solutions(puzzle, &blanks, &DIGITS_ARRAY, &mut solutions);
-    solutions
+    /// Verifies whether a search that has visited nodes grids can go on.
+    fn check(&self, nodes: u64) -> Result<(), Aborted> {
+        if self.max_nodes.is_some_and(|max_nodes| nodes > max_nodes) {
+            return Err(Aborted);
+        }
+
+        #[cfg(feature = "alloc")]
+        if self
+            .cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+        {
+            return Err(Aborted);
+        }
+
+        #[cfg(feature = "std")]
+        if nodes.is_multiple_of(NODES_PER_DEADLINE_CHECK)
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(Aborted);
+        }
+
+        Ok(())
+    }
+}
+
+/// The error of a search that gave up before it finished, as it ran out of its [Budget].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Aborted;
+
+impl Display for Aborted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Aborted: the search ran out of its budget.")
+    }
+}
+
+impl core::error::Error for Aborted {}
+
+/// A backtracking search in progress, with the work done so far and the budget for it.
+struct Search<'a> {
+    stats: SolveStats,
+    budget: &'a Budget,
+}
+
+impl<'a> Search<'a> {
+    fn new(budget: &'a Budget) -> Self {
+        Search {
+            stats: SolveStats::default(),
+            budget,
+        }
+    }
+
+    /// Counts a visit to a grid, and gives up if the budget has run out.
+    fn visit(&mut self) -> Result<(), Aborted> {
+        self.stats.nodes += 1;
+        self.budget.check(self.stats.nodes)
+    }
+
+    /// Counts a digit that fit in a blank, as the fits-th digit to fit there, leaving depth blanks
+    /// filled in. Gives up if the budget has run out.
+    fn fill(&mut self, fits: usize, depth: usize) -> Result<(), Aborted> {
+        if fits == 2 {
+            self.stats.guesses += 1;
+        }
+        self.stats.max_depth = self.stats.max_depth.max(depth);
+        self.visit()
+    }
+}
+
+impl Grid {
+    /// A grid with every cell blank.
+    pub const EMPTY: Grid = Grid([[0; 9]; 9]);
+
+    /// Builds a grid from its rows, or returns None if any cell is not in 0..=9.
+    pub const fn new(cells: [[u8; 9]; 9]) -> Option<Self> {
+        let mut row = 0;
+        while row < 9 {
+            let mut col = 0;
+            while col < 9 {
+                if cells[row][col] > 9 {
+                    return None;
+                }
+                col += 1;
+            }
+            row += 1;
+        }
+
+        Some(Self(cells))
+    }
+
+    /// The rows of the grid, as the raw array.
+    pub fn as_array(&self) -> &[[u8; 9]; 9] {
+        &self.0
+    }
+
+    /// Sets the cell at pos to digit, where 0 is a blank.
+    ///
+    /// Panics if digit is not in 0..=9, or pos is outside the grid.
+    pub fn set(&mut self, (row, col): GridPos, digit: u8) {
+        assert!(digit <= 9, "digit must be 0-9, not {digit}");
+        self.0[row][col] = digit;
+    }
+
+    /// Every cell in reading order.
+    pub fn cells(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.iter().flatten().copied()
+    }
+
+    /// Every row, from the top.
+    pub fn rows(&self) -> impl Iterator<Item = [u8; 9]> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Every col, from the left.
+    pub fn cols(&self) -> impl Iterator<Item = [u8; 9]> + '_ {
+        (0..9).map(|col| array::from_fn(|row| self.0[row][col]))
+    }
+
+    /// Every 3x3 square (or box), in reading order, with its cells in reading order.
+    pub fn squares(&self) -> impl Iterator<Item = [u8; 9]> + '_ {
+        (0..9).map(|square| {
+            array::from_fn(|index| self.0[square / 3 * 3 + index / 3][square % 3 * 3 + index % 3])
+        })
+    }
+
+    /// The 20 other cells that share a row, col or square with pos, in reading order.
+    pub fn peers((row, col): GridPos) -> impl Iterator<Item = GridPos> {
+        (0..81)
+            .map(|index| (index / 9, index % 9))
+            .filter(move |&(other_row, other_col)| {
+                (other_row, other_col) != (row, col)
+                    && (other_row == row
+                        || other_col == col
+                        || (other_row / 3, other_col / 3) == (row / 3, col / 3))
+            })
+    }
+}
+
+impl Index<GridPos> for Grid {
+    type Output = u8;
+
+    fn index(&self, (row, col): GridPos) -> &u8 {
+        &self.0[row][col]
+    }
+}
+
+impl Index<usize> for Grid {
+    type Output = [u8; 9];
+
+    fn index(&self, row: usize) -> &[u8; 9] {
+        &self.0[row]
+    }
+}
+
+impl TryFrom<[[u8; 9]; 9]> for Grid {
+    type Error = InputError;
+
+    fn try_from(cells: [[u8; 9]; 9]) -> Result<Self, InputError> {
+        Self::new(cells).ok_or(InputError::CellOutOfRange)
+    }
+}
+
+impl From<Grid> for [[u8; 9]; 9] {
+    fn from(grid: Grid) -> Self {
+        grid.0
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for digit in self.cells() {
+            write!(
+                f,
+                "{}",
+                if digit == 0 {
+                    '.'
+                } else {
+                    char::from(b'0' + digit)
+                }
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Grid {
+    type Err = InputError;
+
+    /// Parses 81 chars in reading order, with digits 1-9, and `.` or `0` for blanks.
+    fn from_str(s: &str) -> Result<Self, InputError> {
+        if s.chars().count() != 81 {
+            return Err(InputError::WrongCellCount);
+        }
+
+        let mut grid = Grid::EMPTY;
+        for (index, c) in s.chars().enumerate() {
+            let digit = match c {
+                '.' => 0,
+                c => c.to_digit(10).ok_or(InputError::UnknownChar(c))?,
+            };
+            grid.0[index / 9][index % 9] = digit as u8;
+        }
+
+        Ok(grid)
+    }
+}
+
+/// The error of invalid input, e.g. text that is not a [Grid].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InputError {
+    /// A cell was not in 0..=9.
+    CellOutOfRange,
+
+    /// A grid did not have exactly 81 cells.
+    WrongCellCount,
+
+    /// A grid had a char that is not a digit or `.`.
+    UnknownChar(char),
+
+    /// A difficulty was not one of easy, medium, hard or expert.
+    #[cfg(feature = "alloc")]
+    UnknownDifficulty(String),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::CellOutOfRange => f.write_str("Invalid input: grid cells must be 0-9."),
+            InputError::WrongCellCount => {
+                f.write_str("Invalid input: grid must have exactly 81 cells.")
+            }
+            InputError::UnknownChar(c) => {
+                write!(f, "Invalid input: grid has an unknown char {c:?}.")
+            }
+            #[cfg(feature = "alloc")]
+            InputError::UnknownDifficulty(name) => write!(f, "Unknown difficulty: {name}."),
+        }
+    }
+}
+
+impl core::error::Error for InputError {}
+
+/// The variant rules of a puzzle, which need the `alloc` feature. Without it, this has no values,
+/// so the constraints of a search are always empty.
+#[cfg(not(feature = "alloc"))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Constraint {}
+
+#[cfg(not(feature = "alloc"))]
+impl Constraint {
+    fn is_well_formed(&self) -> bool {
+        match *self {}
+    }
+
+    fn covers(&self, _pos: GridPos) -> bool {
+        match *self {}
+    }
+
+    fn is_consistent(&self, _grid: &Grid) -> bool {
+        match *self {}
+    }
+}
+
+/// A source of random numbers for generating puzzles, e.g. [fastrand::Rng] with the `std` feature,
+/// or a hardware generator on an embedded device.
+pub trait Random {
+    /// Returns a random number in 0..bound, where bound is not 0.
+    fn below(&mut self, bound: usize) -> usize;
+
+    /// Returns true or false at random.
+    fn bool(&mut self) -> bool {
+        self.below(2) == 1
+    }
+
+    /// Puts the items of slice in a random order.
+    fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for index in (1..slice.len()).rev() {
+            slice.swap(index, self.below(index + 1));
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Random for Rng {
+    fn below(&mut self, bound: usize) -> usize {
+        self.usize(..bound)
+    }
+
+    fn bool(&mut self) -> bool {
+        Rng::bool(self)
+    }
+
+    fn shuffle<T>(&mut self, slice: &mut [T]) {
+        Rng::shuffle(self, slice);
+    }
+}
+
+/// Finds all solutions to the given puzzle, if any.
+#[cfg(feature = "alloc")]
+pub fn solve(puzzle: Puzzle) -> Vec<Solution> {
+    solve_with_constraints(puzzle, &[])
 }
 
 /// Finds a solution to the given puzzle, if any.
 pub fn solve_any(puzzle: Puzzle) -> Option<Solution> {
-    if !is_valid_puzzle(&puzzle) {
-        return None;
+    solve_any_with_constraints(puzzle, &[])
+}
+
+/// Finds all solutions to the given variant puzzle, if any.
+///
+/// Every solution satisfies the standard rules as well as all of the given [Constraint]s.
+#[cfg(feature = "alloc")]
+pub fn solve_with_constraints(puzzle: Puzzle, constraints: &[Constraint]) -> Vec<Solution> {
+    unlimited(solve_with_budget(puzzle, constraints, &Budget::default()))
+}
+
+/// Finds all solutions to the given variant puzzle the same as [solve_with_constraints], unless
+/// the search runs out of budget first.
+#[cfg(feature = "alloc")]
+pub fn solve_with_budget(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    budget: &Budget,
+) -> Result<Vec<Solution>, Aborted> {
+    if !is_valid_variant_puzzle(&puzzle, constraints) {
+        return Ok(Vec::new());
     }
 
-    let blanks = blanks(puzzle);
-    if blanks.is_empty() {
-        return Some(puzzle);
+    let mut search = Search::new(budget);
+    search.visit()?;
+
+    find_solutions(
+        puzzle,
+        &blanks(puzzle),
+        &DIGITS_ARRAY,
+        constraints,
+        &mut search,
+    )
+}
+
+/// Finds a solution to the given variant puzzle, if any.
+pub fn solve_any_with_constraints(puzzle: Puzzle, constraints: &[Constraint]) -> Option<Solution> {
+    unlimited(solve_any_with_budget(
+        puzzle,
+        constraints,
+        &Budget::default(),
+    ))
+}
+
+/// Finds a solution to the given variant puzzle the same as [solve_any_with_constraints], unless
+/// the search runs out of budget first.
+pub fn solve_any_with_budget(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    budget: &Budget,
+) -> Result<Option<Solution>, Aborted> {
+    find_any_solution(puzzle, constraints, &mut Search::new(budget))
+}
+
+/// Finds a solution to the given variant puzzle the same as [solve_any_with_budget], along with
+/// counts of the work it took, which are kept if the search runs out of budget.
+///
+/// The search is the same as for [solve_any_with_constraints], so it gives the same solution. The
+/// time is only measured with the `std` feature.
+pub fn solve_any_with_stats(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    budget: &Budget,
+) -> (Result<Option<Solution>, Aborted>, SolveStats) {
+    #[cfg(feature = "std")]
+    let start = Instant::now();
+    let mut search = Search::new(budget);
+    let solution = find_any_solution(puzzle, constraints, &mut search);
+
+    #[allow(unused_mut)]
+    let mut stats = search.stats;
+    #[cfg(feature = "std")]
+    {
+        stats.time = start.elapsed();
     }
+    (solution, stats)
+}
 
-    find_solution(puzzle, &blanks, &DIGITS_ARRAY)
+/// Verifies whether a variant puzzle has exactly one solution.
+pub fn has_unique_solution_with_constraints(puzzle: Puzzle, constraints: &[Constraint]) -> bool {
+    count_solutions_with_constraints(puzzle, constraints, 2) == 1
+}
+
+/// Counts the solutions to the given puzzle, stopping at limit.
+pub fn count_solutions(puzzle: Puzzle, limit: usize) -> usize {
+    count_solutions_with_constraints(puzzle, &[], limit)
+}
+
+/// Counts the solutions to the given variant puzzle, stopping at limit.
+pub fn count_solutions_with_constraints(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    limit: usize,
+) -> usize {
+    unlimited(count_solutions_with_budget(
+        puzzle,
+        constraints,
+        limit,
+        &Budget::default(),
+    ))
+}
+
+/// Counts the solutions to the given variant puzzle the same as
+/// [count_solutions_with_constraints], unless the search runs out of budget first.
+pub fn count_solutions_with_budget(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    limit: usize,
+    budget: &Budget,
+) -> Result<usize, Aborted> {
+    count_solutions_in(puzzle, constraints, limit, &mut Search::new(budget))
 }
 
 /// Generates a puzzle with an unique solution. The puzzle will be generally considered as
 /// easy-to-medium difficulty.
+#[cfg(feature = "std")]
 pub fn generate() -> Puzzle {
-    let solution = create_random_solution();
+    generate_with_rng(&mut Rng::new())
+}
 
-    let puzzle =
-        create_random_blank_positions(solution, TARGET_BLANKS_TO_GENERATE).unwrap_or(solution);
-    let puzzle = create_random_blank_row(puzzle).unwrap_or(puzzle);
-    create_random_blank_col(puzzle).unwrap_or(puzzle)
+/// Generates a puzzle the same as `generate`, with random numbers from rng.
+///
+/// The same seed (e.g. see `Rng::with_seed`) always gives the same puzzle, for the same version of
+/// the crate. Use this where the default random numbers are not random, e.g. on
+/// `wasm32-unknown-unknown`, or where there are none, e.g. without the `std` feature.
+pub fn generate_with_rng(rng: &mut impl Random) -> Puzzle {
+    unlimited(generate_with_budget(rng, &Budget::default()))
 }
 
-/// Verifies whether a puzzle has exactly one solution.
-fn has_unique_solution(puzzle: Puzzle) -> bool {
-    if !is_valid_puzzle(&puzzle) {
-        return false;
+/// Generates a puzzle the same as [generate_with_rng], unless it runs out of budget first. The
+/// budget covers all of the searches needed.
+pub fn generate_with_budget(rng: &mut impl Random, budget: &Budget) -> Result<Puzzle, Aborted> {
+    let search = &mut Search::new(budget);
+    let solution = create_random_solution(rng, search)?;
+
+    let puzzle = create_random_blank_positions(solution, TARGET_BLANKS_TO_GENERATE, rng, search)?
+        .unwrap_or(solution);
+    let puzzle = create_random_blank_row(puzzle, rng, search)?.unwrap_or(puzzle);
+    Ok(create_random_blank_col(puzzle, rng, search)?.unwrap_or(puzzle))
+}
+
+/// Generates a variant puzzle with an unique solution, where edge clues between adjacent cells
+/// replace most or all of the givens.
+///
+/// The edge clues are Kropki dots, XV and greater-than signs, see [Constraint].
+#[cfg(feature = "std")]
+pub fn generate_with_edge_clues() -> (Puzzle, Vec<Constraint>) {
+    generate_with_edge_clues_and_rng(&mut Rng::new())
+}
+
+/// Generates a variant puzzle the same as `generate_with_edge_clues`, with random numbers from rng,
+/// see [generate_with_rng].
+#[cfg(feature = "alloc")]
+pub fn generate_with_edge_clues_and_rng(rng: &mut impl Random) -> (Puzzle, Vec<Constraint>) {
+    unlimited(generate_with_edge_clues_and_budget(rng, &Budget::default()))
+}
+
+/// Generates a variant puzzle the same as [generate_with_edge_clues_and_rng], unless it runs out of
+/// budget first, see [generate_with_budget].
+#[cfg(feature = "alloc")]
+pub fn generate_with_edge_clues_and_budget(
+    rng: &mut impl Random,
+    budget: &Budget,
+) -> Result<(Puzzle, Vec<Constraint>), Aborted> {
+    let search = &mut Search::new(budget);
+    let solution = create_random_solution(rng, search)?;
+    let constraints = edge_clues(&solution, rng);
+
+    Ok((
+        create_max_random_blanks(solution, &constraints, rng, search)?,
+        constraints,
+    ))
+}
+
+/// Generates a puzzle with an unique solution, that is rated as difficulty, see [rate].
+///
+/// Starting from a puzzle where no given can be removed, givens from the solution are added back
+/// in random order until the puzzle is easy enough.
+#[cfg(feature = "std")]
+pub fn generate_with_difficulty(difficulty: Difficulty) -> Puzzle {
+    generate_with_difficulty_and_rng(difficulty, &mut Rng::new())
+}
+
+/// Generates a puzzle the same as `generate_with_difficulty`, with random numbers from rng, see
+/// [generate_with_rng].
+#[cfg(feature = "alloc")]
+pub fn generate_with_difficulty_and_rng(difficulty: Difficulty, rng: &mut impl Random) -> Puzzle {
+    unlimited(generate_with_difficulty_and_budget(
+        difficulty,
+        rng,
+        &Budget::default(),
+    ))
+}
+
+/// Generates a puzzle the same as [generate_with_difficulty_and_rng], unless it runs out of budget
+/// first, see [generate_with_budget].
+#[cfg(feature = "alloc")]
+pub fn generate_with_difficulty_and_budget(
+    difficulty: Difficulty,
+    rng: &mut impl Random,
+    budget: &Budget,
+) -> Result<Puzzle, Aborted> {
+    let search = &mut Search::new(budget);
+
+    loop {
+        let solution = create_random_solution(rng, search)?;
+        let minimal = create_max_random_blanks(solution, &[], rng, search)?;
+        if rate(minimal) < difficulty {
+            continue;
+        }
+
+        // Adding givens back is much cheaper than creating the blanks, so try a few orders.
+        for _ in 0..ATTEMPTS_PER_MINIMAL_PUZZLE {
+            let mut puzzle = minimal;
+            let mut positions = blanks(puzzle);
+            rng.shuffle(&mut positions);
+
+            loop {
+                let rating = rate(puzzle);
+                if rating == difficulty {
+                    return Ok(puzzle);
+                }
+
+                // Too easy already, try another order.
+                let Some((row, col)) = positions.pop().filter(|_| rating > difficulty) else {
+                    break;
+                };
+                puzzle.set((row, col), solution[row][col]);
+            }
+        }
+
+        // Rating is not a search, so check the budget between puzzles too.
+        search.budget.check(search.stats.nodes)?;
     }
+}
+
+/// Unwraps the result of a search with an unlimited [Budget], which is never aborted.
+fn unlimited<T>(result: Result<T, Aborted>) -> T {
+    result.unwrap_or_else(|Aborted| unreachable!("an unlimited search is never aborted"))
+}
 
-    let blanks = blanks(puzzle);
-    if blanks.is_empty() {
-        return true;
+/// Finds a solution to the given variant puzzle, if any, with search.
+fn find_any_solution(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    search: &mut Search,
+) -> Result<Option<Solution>, Aborted> {
+    if !is_valid_variant_puzzle(&puzzle, constraints) {
+        return Ok(None);
     }
 
-    let mut count_cache = 0;
-    count_solutions(puzzle, &blanks, &DIGITS_ARRAY, &mut count_cache);
-    count_cache == 1
+    search.visit()?;
+    find_solution(puzzle, &blanks(puzzle), &DIGITS_ARRAY, constraints, search)
+}
+
+/// Counts the solutions to the given variant puzzle with search, stopping at limit.
+fn count_solutions_in(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    limit: usize,
+    search: &mut Search,
+) -> Result<usize, Aborted> {
+    if !is_valid_variant_puzzle(&puzzle, constraints) || limit == 0 {
+        return Ok(0);
+    }
+
+    search.visit()?;
+    count_solutions_up_to(
+        puzzle,
+        &blanks(puzzle),
+        &DIGITS_ARRAY,
+        constraints,
+        limit,
+        search,
+    )
+}
+
+/// Verifies whether a variant puzzle has exactly one solution, with search.
+fn has_unique_solution_in(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    search: &mut Search,
+) -> Result<bool, Aborted> {
+    Ok(count_solutions_in(puzzle, constraints, 2, search)? == 1)
+}
+
+/// Verifies whether a variant puzzle is valid -- all digits are in legal positions, and no
+/// [Constraint] is broken by the digits filled in so far.
+fn is_valid_variant_puzzle(puzzle: &Puzzle, constraints: &[Constraint]) -> bool {
+    is_valid_puzzle(puzzle)
+        && constraints
+            .iter()
+            .all(|constraint| constraint.is_well_formed() && constraint.is_consistent(puzzle))
+}
+
+/// Verifies whether a variant puzzle is still valid after filling in the cell at pos, given that
+/// it was valid before. Only the cell at pos and the [Constraint]s that cover it are checked.
+fn is_valid_fill(puzzle: &Puzzle, constraints: &[Constraint], pos: GridPos) -> bool {
+    !has_conflict(puzzle, pos)
+        && constraints
+            .iter()
+            .filter(|constraint| constraint.covers(pos))
+            .all(|constraint| constraint.is_consistent(puzzle))
 }
 
 /// Verifies whether a puzzle is valid -- all digits are in legal positions.
-fn is_valid_puzzle(puzzle: &Puzzle) -> bool {
+pub(crate) fn is_valid_puzzle(puzzle: &Puzzle) -> bool {
     (0..9).all(|index| {
         let Some(horizontal_slice) = horizontal_slice(puzzle, index) else {
             return false;
@@ -96,8 +776,29 @@ fn is_valid_puzzle(puzzle: &Puzzle) -> bool {
     })
 }
 
-/// Verifies whether a slice has all unique digits, except 0, which is ignored.
-fn slice_has_unique_digits<'a>(slice: impl IntoIterator<Item = &'a u8>) -> bool {
+/// Verifies whether the digit at pos is repeated in its row, col or square. A pos outside of the
+/// grid has no conflict.
+pub fn has_conflict(grid: &Grid, (row, col): GridPos) -> bool {
+    if row >= 9 || col >= 9 {
+        return false;
+    }
+
+    let digit = grid[row][col];
+    if digit == 0 {
+        return false;
+    }
+
+    (0..9).any(|index| {
+        let square = (row / 3 * 3 + index / 3, col / 3 * 3 + index % 3);
+        [(row, index), (index, col), square]
+            .into_iter()
+            .any(|pos| pos != (row, col) && grid[pos.0][pos.1] == digit)
+    })
+}
+
+/// Verifies whether a slice has all unique digits, except 0, which is ignored. A value that is not
+/// a digit is never unique.
+pub(crate) fn slice_has_unique_digits<'a>(slice: impl IntoIterator<Item = &'a u8>) -> bool {
     let mut unique_digits = [false; 9];
 
     for digit in slice {
@@ -105,44 +806,86 @@ fn slice_has_unique_digits<'a>(slice: impl IntoIterator<Item = &'a u8>) -> bool
             continue;
         }
 
-        let index = (digit - 1) as usize;
-        if unique_digits[index] {
-            return false;
+        match unique_digits.get_mut((digit - 1) as usize) {
+            Some(unique) if !*unique => *unique = true,
+            _ => return false,
         }
-
-        unique_digits[index] = true;
     }
 
     true
 }
 
 /// Finds all the blank positions in a [Puzzle] that need to be filled in to form a [Solution].
-fn blanks(puzzle: Puzzle) -> Vec<GridPos> {
-    puzzle
-        .iter()
-        .enumerate()
-        .flat_map(|(row, digits)| {
-            digits.iter().enumerate().filter_map(
-                move |(col, digit)| {
-                    if *digit == 0 { Some((row, col)) } else { None }
-                },
-            )
-        })
-        .collect()
+fn blanks(puzzle: Puzzle) -> Positions {
+    let mut blanks = Positions {
+        positions: [(0, 0); 81],
+        len: 0,
+    };
+    for &pos in Positions::ALL.iter().filter(|&&pos| puzzle[pos] == 0) {
+        blanks.positions[blanks.len] = pos;
+        blanks.len += 1;
+    }
+
+    blanks
+}
+
+/// Up to all 81 positions of a grid, e.g. its blanks, in a fixed array so that searching and
+/// generating never allocate.
+#[derive(Clone, Copy, Debug)]
+struct Positions {
+    positions: [GridPos; 81],
+    len: usize,
+}
+
+impl Positions {
+    /// Every position, in reading order.
+    const ALL: Positions = Positions {
+        positions: {
+            let mut positions = [(0, 0); 81];
+            let mut index = 0;
+            while index < 81 {
+                positions[index] = (index / 9, index % 9);
+                index += 1;
+            }
+            positions
+        },
+        len: 81,
+    };
+
+    /// Removes the last position, if any.
+    #[cfg(feature = "alloc")]
+    fn pop(&mut self) -> Option<GridPos> {
+        self.len = self.len.checked_sub(1)?;
+        Some(self.positions[self.len])
+    }
+}
+
+impl Deref for Positions {
+    type Target = [GridPos];
+
+    fn deref(&self) -> &[GridPos] {
+        &self.positions[..self.len]
+    }
+}
+
+impl DerefMut for Positions {
+    fn deref_mut(&mut self) -> &mut [GridPos] {
+        &mut self.positions[..self.len]
+    }
 }
 
 /// Gets a view of a row in a [Puzzle].
-fn horizontal_slice(puzzle: &Puzzle, row: usize) -> Option<impl Iterator<Item = &u8>> {
-    Some(puzzle.get(row)?.iter())
+pub(crate) fn horizontal_slice(puzzle: &Puzzle, row: usize) -> Option<impl Iterator<Item = &u8>> {
+    Some(puzzle.as_array().get(row)?.iter())
 }
 
 /// Gets a view of a col in a [Puzzle].
-fn vertical_slice(puzzle: &Puzzle, col: usize) -> Option<impl Iterator<Item = &u8>> {
+pub(crate) fn vertical_slice(puzzle: &Puzzle, col: usize) -> Option<impl Iterator<Item = &u8>> {
     if !(0..9).contains(&col) {
         return None;
     }
 
-    Some(puzzle.iter().map(move |row| &row[col]))
+    Some(puzzle.as_array().iter().map(move |row| &row[col]))
 }
 
 /// Gets a view of a square in a [Puzzle].
@@ -167,7 +910,7 @@ fn vertical_slice(puzzle: &Puzzle, col: usize) -> Option<impl Iterator<Item = &u
 /// |678|
 /// +---+
 /// ```
-fn square_slice(puzzle: &Puzzle, square: usize) -> Option<impl Iterator<Item = &u8>> {
+pub(crate) fn square_slice(puzzle: &Puzzle, square: usize) -> Option<impl Iterator<Item = &u8>> {
     Some(
         match square {
             0 => [
@@ -275,197 +1018,273 @@ fn square_slice(puzzle: &Puzzle, square: usize) -> Option<impl Iterator<Item = &
     )
 }
 
-/// Finds a [Solution] to a [Puzzle] by backtracking.
-///
-/// digits is the sequence of digits to use for searching. For all practical purposes, digits should
-/// contain all of 1..=9.
-fn find_solution(mut puzzle: Puzzle, blanks: &[GridPos], digits: &[u8; 9]) -> Option<Solution> {
-    if blanks.is_empty() {
-        // We have run out of blanks to fill, so this is a solution.
-        return Some(puzzle);
-    }
-
-    let (row, col) = blanks[0];
-
-    for digit in digits {
-        puzzle[row][col] = *digit;
-
-        if !is_valid_puzzle(&puzzle) {
-            continue;
-        }
-
-        if let Some(solution) = find_solution(puzzle, &blanks[1..], digits) {
-            return Some(solution);
-        }
-    }
-
-    None
+/// Finds a [Solution] to a [Puzzle] by backtracking, see [backtrack].
+fn find_solution(
+    mut puzzle: Puzzle,
+    blanks: &[GridPos],
+    digits: &[u8; 9],
+    constraints: &[Constraint],
+    search: &mut Search,
+) -> Result<Option<Solution>, Aborted> {
+    let found = backtrack(&mut puzzle, blanks, digits, constraints, search, |_| true)?;
+    Ok(found.then_some(puzzle))
 }
 
-/// Finds all [Solution]s to a [Puzzle].
-///
-/// Returns the solutions in the variable. If no solution is found, the Vec will be empty.
-///
-/// digits is the sequence of digits to use for searching. For all practical purposes, digits should
-/// contain all of 1..=9.
+/// Finds all [Solution]s to a [Puzzle] by backtracking, see [backtrack]. If no solution is found,
+/// the Vec will be empty.
+#[cfg(feature = "alloc")]
 fn find_solutions(
     mut puzzle: Puzzle,
     blanks: &[GridPos],
     digits: &[u8; 9],
-    solutions: &mut Vec<Solution>,
-) {
-    if blanks.is_empty() {
-        // We have run out of blanks to fill, so this is a solution.
-        solutions.push(puzzle);
-
-        return;
-    }
-
-    let (row, col) = blanks[0];
-
-    for digit in digits {
-        puzzle[row][col] = *digit;
-
-        if !is_valid_puzzle(&puzzle) {
-            continue;
-        }
+    constraints: &[Constraint],
+    search: &mut Search,
+) -> Result<Vec<Solution>, Aborted> {
+    let mut solutions = Vec::new();
+    backtrack(
+        &mut puzzle,
+        blanks,
+        digits,
+        constraints,
+        search,
+        |solution| {
+            solutions.push(*solution);
+            false
+        },
+    )?;
+    Ok(solutions)
+}
 
-        find_solutions(puzzle, &blanks[1..], digits, solutions);
-    }
+/// Counts the [Solution]s of a [Puzzle] by backtracking, up to limit, see [backtrack].
+fn count_solutions_up_to(
+    mut puzzle: Puzzle,
+    blanks: &[GridPos],
+    digits: &[u8; 9],
+    constraints: &[Constraint],
+    limit: usize,
+    search: &mut Search,
+) -> Result<usize, Aborted> {
+    let mut count = 0;
+    backtrack(&mut puzzle, blanks, digits, constraints, search, |_| {
+        count += 1;
+        count >= limit
+    })?;
+    Ok(count)
 }
 
-/// Checks whether a [Puzzle] has 0, 1 or 2 or more [Solution]s.
+/// Fills in the blanks of a [Puzzle] by backtracking, calling on_solution with each [Solution]
+/// until it returns true to stop the search.
+///
+/// Returns whether on_solution stopped the search, in which case puzzle holds the last solution.
+/// Otherwise, every blank is blank again. The search is iterative and fills in puzzle in place,
+/// with its state in fixed arrays, so it never recurses or allocates.
 ///
-/// Returns the number of solutions (0, 1, or 2) in count_cache. If the puzzle has two or more
-/// solutions, count_cache will be 2.
+/// blanks are the positions to fill, in order. digits is the sequence of digits to use for
+/// searching. For all practical purposes, digits should contain all of 1..=9.
 ///
-/// digits is the sequence of digits to use for searching. For all practical purposes, digits should
-/// contain all of 1..=9.
-fn count_solutions(mut puzzle: Puzzle, blanks: &[GridPos], digits: &[u8; 9], count_cache: &mut u8) {
-    if blanks.is_empty() {
-        // We have run out of blanks to fill, so this is a solution.
-        *count_cache += 1;
+/// constraints are the additional variant rules that the [Solution]s must satisfy.
+///
+/// search counts the work done, and gives up once its budget has run out.
+fn backtrack(
+    puzzle: &mut Puzzle,
+    blanks: &[GridPos],
+    digits: &[u8; 9],
+    constraints: &[Constraint],
+    search: &mut Search,
+    mut on_solution: impl FnMut(&Solution) -> bool,
+) -> Result<bool, Aborted> {
+    assert!(blanks.len() <= 81, "a puzzle has at most 81 blanks");
+
+    // For each filled blank, the index in digits of the next digit to try, the number of digits
+    // that fit so far, and the number of solutions found before its digit was filled in.
+    let mut next_digit = [0; 81];
+    let mut fits = [0; 81];
+    let mut found_before = [0u64; 81];
+    let mut found = 0;
+    let mut depth = 0;
 
-        return;
-    }
+    loop {
+        if depth == blanks.len() {
+            // We have run out of blanks to fill, so this is a solution.
+            found += 1;
+            if on_solution(puzzle) {
+                return Ok(true);
+            }
+        } else {
+            let pos = blanks[depth];
 
-    let (row, col) = blanks[0];
+            // Try the remaining digits in this blank, until one fits.
+            let mut filled = false;
+            while next_digit[depth] < digits.len() && !filled {
+                puzzle.set(pos, digits[next_digit[depth]]);
+                next_digit[depth] += 1;
 
-    for digit in digits {
-        puzzle[row][col] = *digit;
+                search.stats.propagations += 1;
+                filled = is_valid_fill(puzzle, constraints, pos);
+            }
 
-        if !is_valid_puzzle(&puzzle) {
-            continue;
+            if filled {
+                fits[depth] += 1;
+                search.fill(fits[depth], depth + 1)?;
+                found_before[depth] = found;
+                depth += 1;
+                continue;
+            }
+
+            // No digit is left to try, so undo this blank.
+            puzzle.set(pos, 0);
+            next_digit[depth] = 0;
+            fits[depth] = 0;
         }
 
-        count_solutions(puzzle, &blanks[1..], digits, count_cache);
-        if *count_cache > 1 {
-            return;
+        // Go back to the previous blank, to try its next digit.
+        let Some(previous) = depth.checked_sub(1) else {
+            return Ok(false);
+        };
+        depth = previous;
+        if found == found_before[depth] {
+            search.stats.backtracks += 1;
         }
     }
 }
 
-/// Creates a random [Solution].
-fn create_random_solution() -> Solution {
+/// Creates a random [Solution] with search.
+fn create_random_solution(rng: &mut impl Random, search: &mut Search) -> Result<Solution, Aborted> {
     let mut digits = DIGITS_ARRAY;
-    fastrand::shuffle(&mut digits);
+    rng.shuffle(&mut digits);
 
     // Search for a solution for an empty puzzle, but we jumble up the digits to fill.
 
-    let puzzle = [[0; 9]; 9];
-    let blanks: Vec<GridPos> = (0..9)
-        .flat_map(|row| (0..9).map(move |col| (row, col)))
-        .collect();
+    let puzzle = Grid::EMPTY;
+    let blanks = Positions::ALL;
 
     loop {
-        if let Some(solution) = find_solution(puzzle, &blanks, &digits) {
-            return solution;
+        search.visit()?;
+        if let Some(solution) = find_solution(puzzle, &blanks, &digits, &[], search)? {
+            return Ok(solution);
         }
     }
 }
 
-/// Creates up to count random blanks in the given [Puzzle]. Ensures that the resultant [Puzzle]
-/// retains its unique [Solution].
-fn create_random_blank_positions(puzzle: Puzzle, count: usize) -> Option<Puzzle> {
+/// Creates up to count random blanks in the given [Puzzle] with search. Ensures that the
+/// resultant [Puzzle] retains its unique [Solution].
+fn create_random_blank_positions(
+    puzzle: Puzzle,
+    count: usize,
+    rng: &mut impl Random,
+    search: &mut Search,
+) -> Result<Option<Puzzle>, Aborted> {
     if !(1..=MAX_BLANKS_TO_GENERATE).contains(&count) {
-        return None;
+        return Ok(None);
     }
 
-    let mut positions: Vec<GridPos> = (0..9)
-        .flat_map(|row| (0..9).map(move |col| (row, col)))
-        .collect();
-    fastrand::shuffle(&mut positions);
+    let mut positions = Positions::ALL;
+    rng.shuffle(&mut positions);
 
     let mut puzzle = puzzle;
 
     let mut blanks_created = 0;
-    for (row, col) in positions {
+    for &(row, col) in positions.iter() {
         if blanks_created == count {
             break;
         }
 
         let orig_digit = puzzle[row][col];
-        puzzle[row][col] = 0;
+        puzzle.set((row, col), 0);
 
-        if has_unique_solution(puzzle) {
+        if has_unique_solution_in(puzzle, &[], search)? {
             blanks_created += 1;
         } else {
-            puzzle[row][col] = orig_digit;
+            puzzle.set((row, col), orig_digit);
         }
     }
 
-    Some(puzzle)
+    Ok(Some(puzzle))
 }
 
-/// Creates a randomly chosen blank row in the given [Puzzle]. Ensures that the resultant [Puzzle]
-/// retains its unique [Solution].
+/// Creates as many random blanks as possible in the given variant [Puzzle] with search. Ensures
+/// that the resultant [Puzzle] retains its unique [Solution].
+#[cfg(feature = "alloc")]
+fn create_max_random_blanks(
+    puzzle: Puzzle,
+    constraints: &[Constraint],
+    rng: &mut impl Random,
+    search: &mut Search,
+) -> Result<Puzzle, Aborted> {
+    let mut positions = Positions::ALL;
+    rng.shuffle(&mut positions);
+
+    let mut puzzle = puzzle;
+
+    for &(row, col) in positions.iter() {
+        let orig_digit = puzzle[row][col];
+        puzzle.set((row, col), 0);
+
+        if !has_unique_solution_in(puzzle, constraints, search)? {
+            puzzle.set((row, col), orig_digit);
+        }
+    }
+
+    Ok(puzzle)
+}
+
+/// Creates a randomly chosen blank row in the given [Puzzle] with search. Ensures that the
+/// resultant [Puzzle] retains its unique [Solution].
 ///
 /// Returns None if every row fails to retain the unique [Solution] after blanking.
-fn create_random_blank_row(puzzle: Puzzle) -> Option<Puzzle> {
+fn create_random_blank_row(
+    puzzle: Puzzle,
+    rng: &mut impl Random,
+    search: &mut Search,
+) -> Result<Option<Puzzle>, Aborted> {
     let mut rows = INDICES_ARRAY;
-    fastrand::shuffle(&mut rows);
+    rng.shuffle(&mut rows);
 
     for row in rows {
         let mut puzzle = puzzle;
         for col in 0..9 {
-            puzzle[row][col] = 0;
+            puzzle.set((row, col), 0);
         }
 
-        if has_unique_solution(puzzle) {
-            return Some(puzzle);
+        if has_unique_solution_in(puzzle, &[], search)? {
+            return Ok(Some(puzzle));
         }
     }
 
-    None
+    Ok(None)
 }
 
-/// Creates a randomly chosen blank col in the given [Puzzle]. Ensures that the resultant [Puzzle]
-/// retains its unique [Solution].
+/// Creates a randomly chosen blank col in the given [Puzzle] with search. Ensures that the
+/// resultant [Puzzle] retains its unique [Solution].
 ///
 /// Returns None if every row fails to retain the unique [Solution] after blanking.
-fn create_random_blank_col(puzzle: Puzzle) -> Option<Puzzle> {
+fn create_random_blank_col(
+    puzzle: Puzzle,
+    rng: &mut impl Random,
+    search: &mut Search,
+) -> Result<Option<Puzzle>, Aborted> {
     let mut cols = INDICES_ARRAY;
-    fastrand::shuffle(&mut cols);
+    rng.shuffle(&mut cols);
 
     for col in cols {
         let mut puzzle = puzzle;
-        for row in puzzle.iter_mut() {
-            row[col] = 0;
+        for row in 0..9 {
+            puzzle.set((row, col), 0);
         }
 
-        if has_unique_solution(puzzle) {
-            return Some(puzzle);
+        if has_unique_solution_in(puzzle, &[], search)? {
+            return Ok(Some(puzzle));
         }
     }
 
-    None
+    Ok(None)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
-    const SLICE_TEST_1: Puzzle = [
+    const SLICE_TEST_1: Puzzle = Grid::new([
         [0, 0, 0, 1, 1, 1, 2, 2, 2],
         [0, 0, 0, 1, 1, 1, 2, 2, 2],
         [0, 0, 0, 1, 1, 1, 2, 2, 2],
@@ -475,8 +1294,9 @@ mod tests {
         [6, 6, 6, 7, 7, 7, 8, 8, 8],
         [6, 6, 6, 7, 7, 7, 8, 8, 8],
         [6, 6, 6, 7, 7, 7, 8, 8, 8],
-    ];
-    const SLICE_TEST_2: Puzzle = [
+    ])
+    .unwrap();
+    const SLICE_TEST_2: Puzzle = Grid::new([
         [1, 2, 3, 1, 2, 3, 1, 2, 3],
         [4, 5, 6, 4, 5, 6, 4, 5, 6],
         [7, 8, 9, 7, 8, 9, 7, 8, 9],
@@ -486,7 +1306,8 @@ mod tests {
         [1, 2, 3, 1, 2, 3, 1, 2, 3],
         [4, 5, 6, 4, 5, 6, 4, 5, 6],
         [7, 8, 9, 7, 8, 9, 7, 8, 9],
-    ];
+    ])
+    .unwrap();
 
     #[test]
     fn check_horizontal_slice() {
@@ -633,37 +1454,251 @@ mod tests {
         assert!(!slice_has_unique_digits(&[9, 8, 7, 6, 5, 4, 3, 2, 2]));
 
         assert!(slice_has_unique_digits(&[0; 9]));
+        assert!(!slice_has_unique_digits(&[10, 0, 0, 0, 0, 0, 0, 0, 0]));
+        assert!(!slice_has_unique_digits(&[255; 9]));
+    }
+
+    #[test]
+    fn check_has_conflict() {
+        let mut grid = Grid::EMPTY;
+        grid.set((0, 0), 5);
+        grid.set((1, 1), 5);
+        grid.set((4, 4), 5);
+
+        assert!(has_conflict(&grid, (0, 0)));
+        assert!(has_conflict(&grid, (1, 1)));
+        assert!(!has_conflict(&grid, (4, 4)));
+        assert!(!has_conflict(&grid, (0, 1)));
+
+        assert!(!has_conflict(&grid, (9, 0)));
+        assert!(!has_conflict(&grid, (0, 9)));
+        assert!(!has_conflict(&grid, (usize::MAX, usize::MAX)));
+    }
+
+    fn random_solution() -> Solution {
+        create_random_solution(&mut Rng::new(), &mut Search::new(&Budget::default())).unwrap()
     }
 
     #[test]
     fn check_random_solution() {
-        let solution = create_random_solution();
+        let solution = random_solution();
 
         assert!(is_valid_puzzle(&solution));
-        assert!(solution.into_iter().flatten().all(|digit| digit != 0));
+        assert!(solution.cells().all(|digit| digit != 0));
     }
 
     #[test]
     fn check_random_blanks() {
-        let solution = create_random_solution();
-        assert_eq!(
-            solution
-                .iter()
-                .flatten()
-                .filter(|&digit| *digit == 0)
-                .count(),
-            0
-        );
+        let solution = random_solution();
+        assert_eq!(solution.cells().filter(|&digit| digit == 0).count(), 0);
 
         // TARGET_BLANKS_TO_GENERATE should never fail.
-        let puzzle = create_random_blank_positions(solution, TARGET_BLANKS_TO_GENERATE);
+        let puzzle = create_random_blank_positions(
+            solution,
+            TARGET_BLANKS_TO_GENERATE,
+            &mut Rng::new(),
+            &mut Search::new(&Budget::default()),
+        )
+        .unwrap();
         assert!(puzzle.is_some());
 
         if let Some(puzzle) = puzzle {
             assert_eq!(
-                puzzle.iter().flatten().filter(|&digit| *digit == 0).count(),
+                puzzle.cells().filter(|&digit| digit == 0).count(),
                 TARGET_BLANKS_TO_GENERATE
             );
         }
     }
+
+    #[test]
+    fn check_generate_with_random() {
+        // A xorshift generator, as an embedded caller without fastrand might supply.
+        struct Xorshift(u64);
+
+        impl Random for Xorshift {
+            fn below(&mut self, bound: usize) -> usize {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                (self.0 % bound as u64) as usize
+            }
+        }
+
+        let puzzle = generate_with_rng(&mut Xorshift(42));
+
+        assert_eq!(puzzle, generate_with_rng(&mut Xorshift(42)));
+        assert!(has_unique_solution_with_constraints(puzzle, &[]));
+    }
+
+    #[test]
+    fn check_generate_with_difficulty() {
+        let puzzle = generate_with_difficulty(Difficulty::Easy);
+
+        assert_eq!(rate(puzzle), Difficulty::Easy);
+        assert!(has_unique_solution_with_constraints(puzzle, &[]));
+
+        // Harder puzzles can take many minimal puzzles to find, so use seeds that find one soon.
+        let budget = Budget {
+            max_nodes: Some(1_000_000),
+            ..Default::default()
+        };
+        for difficulty in [Difficulty::Medium, Difficulty::Expert] {
+            let puzzle =
+                generate_with_difficulty_and_budget(difficulty, &mut Rng::with_seed(1), &budget)
+                    .unwrap();
+
+            assert_eq!(rate(puzzle), difficulty);
+            assert!(has_unique_solution_with_constraints(puzzle, &[]));
+        }
+
+        let budget = Budget {
+            max_nodes: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(
+            generate_with_difficulty_and_budget(Difficulty::Hard, &mut Rng::new(), &budget),
+            Err(Aborted)
+        );
+    }
+
+    #[test]
+    fn check_count_solutions() {
+        let solution = random_solution();
+        assert_eq!(count_solutions(solution, 10), 1);
+        assert_eq!(count_solutions(Grid::EMPTY, 10), 10);
+        assert_eq!(count_solutions(Grid::EMPTY, 0), 0);
+
+        let mut puzzle = solution;
+        puzzle.set((0, 0), puzzle[0][1]);
+        assert_eq!(count_solutions(puzzle, 10), 0);
+    }
+
+    #[test]
+    fn check_solve_stats() {
+        let solution = random_solution();
+        let (found, stats) = solve_any_with_stats(solution, &[], &Budget::default());
+        assert_eq!(found, Ok(Some(solution)));
+        assert_eq!(
+            (stats.nodes, stats.max_depth, stats.propagations),
+            (1, 0, 0)
+        );
+
+        let mut puzzle = solution;
+        puzzle.set((4, 4), 0);
+        let (found, stats) = solve_any_with_stats(puzzle, &[], &Budget::default());
+        assert_eq!(found, Ok(Some(solution)));
+        assert_eq!((stats.guesses, stats.backtracks), (0, 0));
+        assert_eq!((stats.nodes, stats.max_depth), (2, 1));
+        assert_eq!(stats.propagations, u64::from(solution[4][4]));
+
+        let (found, stats) = solve_any_with_stats(Grid::EMPTY, &[], &Budget::default());
+        assert_eq!(found, Ok(solve_any(Grid::EMPTY)));
+        assert_eq!(stats.max_depth, 81);
+        assert_eq!(stats.nodes, 1 + 81 + stats.backtracks);
+        assert!(stats.guesses > 0 && stats.propagations >= stats.nodes);
+
+        puzzle.set((0, 0), puzzle[0][1]);
+        let (found, stats) = solve_any_with_stats(puzzle, &[], &Budget::default());
+        assert_eq!(found, Ok(None));
+        assert_eq!(stats.nodes, 0);
+    }
+
+    #[test]
+    fn check_backtrack() {
+        let solution = random_solution();
+        let mut puzzle = solution;
+        for pos in [(0, 0), (4, 4), (8, 8)] {
+            puzzle.set(pos, 0);
+        }
+        let blanks = blanks(puzzle);
+        let budget = Budget::default();
+        let search = &mut Search::new(&budget);
+
+        let mut solved = puzzle;
+        assert_eq!(
+            backtrack(&mut solved, &blanks, &DIGITS_ARRAY, &[], search, |_| true),
+            Ok(true)
+        );
+        assert_eq!(solved, solution);
+
+        // Without stopping, every blank is undone.
+        let mut exhausted = puzzle;
+        let mut found = 0;
+        let result = backtrack(&mut exhausted, &blanks, &DIGITS_ARRAY, &[], search, |_| {
+            found += 1;
+            false
+        });
+        assert_eq!((result, found), (Ok(false), 1));
+        assert_eq!(exhausted, puzzle);
+    }
+
+    #[test]
+    fn check_budget() {
+        let solution = random_solution();
+        let budget = Budget {
+            max_nodes: Some(1000),
+            ..Default::default()
+        };
+        assert_eq!(
+            solve_any_with_budget(solution, &[], &budget),
+            Ok(Some(solution))
+        );
+        assert_eq!(
+            count_solutions_with_budget(Grid::EMPTY, &[], usize::MAX, &budget),
+            Err(Aborted)
+        );
+
+        let (found, stats) = solve_any_with_stats(Grid::EMPTY, &[], &budget);
+        assert_eq!(found, Ok(solve_any(Grid::EMPTY)));
+        assert!(stats.nodes <= 1000);
+        let (found, stats) = solve_any_with_stats(
+            Grid::EMPTY,
+            &[],
+            &Budget {
+                max_nodes: Some(10),
+                ..Default::default()
+            },
+        );
+        assert_eq!((found, stats.nodes), (Err(Aborted), 11));
+
+        let cancel = Arc::new(AtomicBool::new(true));
+        let budget = Budget {
+            cancel: Some(Arc::clone(&cancel)),
+            ..Default::default()
+        };
+        assert_eq!(solve_with_budget(solution, &[], &budget), Err(Aborted));
+        cancel.store(false, Ordering::Relaxed);
+        assert_eq!(
+            solve_with_budget(solution, &[], &budget),
+            Ok(vec![solution])
+        );
+
+        let budget = Budget::with_timeout(Duration::ZERO);
+        assert_eq!(
+            generate_with_edge_clues_and_budget(&mut Rng::new(), &budget),
+            Err(Aborted)
+        );
+    }
+
+    #[test]
+    fn check_grid() {
+        assert!(Grid::new([[10; 9]; 9]).is_none());
+        assert!(Grid::try_from([[255; 9]; 9]).is_err());
+
+        let grid = SLICE_TEST_1;
+        assert_eq!(grid.to_string().parse::<Grid>().unwrap(), grid);
+        assert_eq!("0".repeat(81).parse::<Grid>().unwrap(), Grid::EMPTY);
+        assert!("123".parse::<Grid>().is_err());
+        assert!("x".repeat(81).parse::<Grid>().is_err());
+
+        assert_eq!(grid[(1, 3)], grid[1][3]);
+        assert_eq!(grid.rows().nth(1), Some(grid[1]));
+        assert_eq!(grid.cols().nth(2).unwrap()[1], grid[1][2]);
+        assert_eq!(grid.squares().nth(4).unwrap()[0], grid[3][3]);
+        assert_eq!(<[[u8; 9]; 9]>::from(grid), *grid.as_array());
+
+        let peers = Grid::peers((4, 4)).collect::<Vec<_>>();
+        assert_eq!(peers.len(), 20);
+        assert!(peers.contains(&(3, 5)) && !peers.contains(&(4, 4)));
+    }
 }
@@ -0,0 +1,693 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+#[cfg(any(feature = "std", feature = "serde"))]
+use serde::{Deserialize, Serialize};
+
+use crate::sudoku::{
+    Grid, GridPos, Random, horizontal_slice, slice_has_unique_digits, square_slice, vertical_slice,
+};
+
+/// An additional rule on top of the standard Sudoku rules.
+///
+/// Cells are given as (row, col), with both row and col in 0..=8.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    any(feature = "std", feature = "serde"),
+    derive(Serialize, Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum Constraint {
+    /// Digits strictly increase along the cells, starting from the bulb (the first cell).
+    Thermometer { cells: Vec<GridPos> },
+
+    /// The digits along the arrow sum to the number in the circle.
+    ///
+    /// The circle usually has a single cell. If it has more, then its digits are read in order to
+    /// form a multi-digit number.
+    Arrow {
+        circle: Vec<GridPos>,
+        arrow: Vec<GridPos>,
+    },
+
+    /// The digits between the 1 and the 9 in the line sum to sum.
+    Sandwich { line: Line, sum: u8 },
+
+    /// A white Kropki dot: the two adjacent cells hold consecutive digits.
+    WhiteDot { cells: [GridPos; 2] },
+
+    /// A black Kropki dot: one of the two adjacent cells holds double the digit of the other.
+    BlackDot { cells: [GridPos; 2] },
+
+    /// The two adjacent cells sum to 10.
+    X { cells: [GridPos; 2] },
+
+    /// The two adjacent cells sum to 5.
+    V { cells: [GridPos; 2] },
+
+    /// The first of the two adjacent cells holds a greater digit than the second.
+    GreaterThan { cells: [GridPos; 2] },
+}
+
+/// A row or col in a [Grid], in 0..=8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    any(feature = "std", feature = "serde"),
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Line {
+    Row(usize),
+    Col(usize),
+}
+
+/// A rule that is broken by a [Grid].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// A row, in 0..=8, has a repeated digit.
+    Row(usize),
+
+    /// A col, in 0..=8, has a repeated digit.
+    Col(usize),
+
+    /// A square, in 0..=8, has a repeated digit.
+    Square(usize),
+
+    /// The [Constraint] at the given index is broken.
+    Clue(usize),
+}
+
+impl Constraint {
+    /// Verifies whether the constraint only refers to cells inside the [Grid], and can possibly be
+    /// satisfied.
+    pub fn is_well_formed(&self) -> bool {
+        let in_grid = |cells: &[GridPos]| cells.iter().all(|(row, col)| *row < 9 && *col < 9);
+
+        match self {
+            Constraint::Thermometer { cells } => {
+                (2..=9).contains(&cells.len())
+                    && in_grid(cells)
+                    && !has_repeated_cells(cells.iter())
+            }
+            Constraint::Arrow { circle, arrow } => {
+                (1..=3).contains(&circle.len())
+                    && !arrow.is_empty()
+                    && in_grid(circle)
+                    && in_grid(arrow)
+                    && !has_repeated_cells(circle.iter().chain(arrow))
+            }
+            Constraint::Sandwich { line, sum } => {
+                let (Line::Row(index) | Line::Col(index)) = line;
+                *index < 9 && *sum <= 35
+            }
+            Constraint::WhiteDot { cells }
+            | Constraint::BlackDot { cells }
+            | Constraint::X { cells }
+            | Constraint::V { cells }
+            | Constraint::GreaterThan { cells } => {
+                let [(row_a, col_a), (row_b, col_b)] = *cells;
+                in_grid(cells) && row_a.abs_diff(row_b) + col_a.abs_diff(col_b) == 1
+            }
+        }
+    }
+
+    /// Verifies whether the constraint involves the cell at pos.
+    pub fn covers(&self, pos: GridPos) -> bool {
+        match self {
+            Constraint::Thermometer { cells } => cells.contains(&pos),
+            Constraint::Arrow { circle, arrow } => circle.contains(&pos) || arrow.contains(&pos),
+            Constraint::Sandwich { line, .. } => line.cells().contains(&pos),
+            Constraint::WhiteDot { cells }
+            | Constraint::BlackDot { cells }
+            | Constraint::X { cells }
+            | Constraint::V { cells }
+            | Constraint::GreaterThan { cells } => cells.contains(&pos),
+        }
+    }
+
+    /// Gets the two adjacent cells of an edge clue, or None for other types of constraint.
+    pub fn edge(&self) -> Option<[GridPos; 2]> {
+        match self {
+            Constraint::WhiteDot { cells }
+            | Constraint::BlackDot { cells }
+            | Constraint::X { cells }
+            | Constraint::V { cells }
+            | Constraint::GreaterThan { cells } => Some(*cells),
+            _ => None,
+        }
+    }
+
+    /// Verifies whether the digits filled in so far can still satisfy the constraint.
+    ///
+    /// When all the cells covered by the constraint are filled in, this is an exact check.
+    ///
+    /// The constraint must be well-formed, see [Constraint::is_well_formed].
+    pub fn is_consistent(&self, grid: &Grid) -> bool {
+        match self {
+            Constraint::Thermometer { cells } => is_thermometer_consistent(grid, cells),
+            Constraint::Arrow { circle, arrow } => is_arrow_consistent(grid, circle, arrow),
+            Constraint::Sandwich { line, sum } => is_sandwich_consistent(grid, *line, *sum),
+            Constraint::WhiteDot { cells } => {
+                is_pair_consistent(grid, cells, |a, b| a.abs_diff(b) == 1)
+            }
+            Constraint::BlackDot { cells } => {
+                is_pair_consistent(grid, cells, |a, b| a == b * 2 || b == a * 2)
+            }
+            Constraint::X { cells } => is_pair_consistent(grid, cells, |a, b| a + b == 10),
+            Constraint::V { cells } => is_pair_consistent(grid, cells, |a, b| a + b == 5),
+            Constraint::GreaterThan { cells } => is_pair_consistent(grid, cells, |a, b| a > b),
+        }
+    }
+
+    /// Gets a short name for the type of constraint.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Constraint::Thermometer { .. } => "thermometer",
+            Constraint::Arrow { .. } => "arrow",
+            Constraint::Sandwich { .. } => "sandwich",
+            Constraint::WhiteDot { .. } => "white dot",
+            Constraint::BlackDot { .. } => "black dot",
+            Constraint::X { .. } => "X",
+            Constraint::V { .. } => "V",
+            Constraint::GreaterThan { .. } => "greater-than",
+        }
+    }
+}
+
+impl Line {
+    /// Gets the positions of the cells in the line, in order.
+    pub fn cells(self) -> [GridPos; 9] {
+        match self {
+            Line::Row(row) => [0, 1, 2, 3, 4, 5, 6, 7, 8].map(|col| (row, col)),
+            Line::Col(col) => [0, 1, 2, 3, 4, 5, 6, 7, 8].map(|row| (row, col)),
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Row(row) => write!(f, "row {} has a repeated digit", row + 1),
+            Violation::Col(col) => write!(f, "col {} has a repeated digit", col + 1),
+            Violation::Square(square) => write!(f, "square {} has a repeated digit", square + 1),
+            Violation::Clue(index) => write!(f, "clue {} is broken", index + 1),
+        }
+    }
+}
+
+/// Finds all the rules that are broken by the digits in a [Grid].
+///
+/// Blanks are ignored, so a partially filled grid only reports rules that can no longer be
+/// satisfied. A malformed [Constraint] is always reported as broken.
+pub fn validate(grid: &Grid, constraints: &[Constraint]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for index in 0..9 {
+        if !horizontal_slice(grid, index).is_some_and(slice_has_unique_digits) {
+            violations.push(Violation::Row(index));
+        }
+    }
+    for index in 0..9 {
+        if !vertical_slice(grid, index).is_some_and(slice_has_unique_digits) {
+            violations.push(Violation::Col(index));
+        }
+    }
+    for index in 0..9 {
+        if !square_slice(grid, index).is_some_and(slice_has_unique_digits) {
+            violations.push(Violation::Square(index));
+        }
+    }
+
+    violations.extend(
+        constraints
+            .iter()
+            .enumerate()
+            .filter(|(_, constraint)| {
+                !constraint.is_well_formed() || !constraint.is_consistent(grid)
+            })
+            .map(|(index, _)| Violation::Clue(index)),
+    );
+
+    violations
+}
+
+/// Finds the edge clues that hold between adjacent cells in a [Grid] that has no blanks.
+///
+/// Every pair that qualifies for a Kropki dot or XV gets one, preferring a white dot over a black
+/// dot for 1 and 2. About half of the remaining pairs get a greater-than sign.
+pub(crate) fn edge_clues(solution: &Grid, rng: &mut impl Random) -> Vec<Constraint> {
+    let pairs = (0..9).flat_map(|row| {
+        (0..9).flat_map(move |col| {
+            let right = (col < 8).then_some([(row, col), (row, col + 1)]);
+            let down = (row < 8).then_some([(row, col), (row + 1, col)]);
+            right.into_iter().chain(down)
+        })
+    });
+
+    pairs
+        .filter_map(|cells| {
+            let [(row_a, col_a), (row_b, col_b)] = cells;
+            let (a, b) = (solution[row_a][col_a], solution[row_b][col_b]);
+
+            if a.abs_diff(b) == 1 {
+                Some(Constraint::WhiteDot { cells })
+            } else if a == b * 2 || b == a * 2 {
+                Some(Constraint::BlackDot { cells })
+            } else if a + b == 10 {
+                Some(Constraint::X { cells })
+            } else if a + b == 5 {
+                Some(Constraint::V { cells })
+            } else if rng.bool() {
+                let [first, second] = cells;
+                Some(Constraint::GreaterThan {
+                    cells: if a > b {
+                        [first, second]
+                    } else {
+                        [second, first]
+                    },
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Verifies whether a list of cells refers to any cell more than once.
+fn has_repeated_cells<'a>(cells: impl Iterator<Item = &'a GridPos> + Clone) -> bool {
+    cells
+        .clone()
+        .enumerate()
+        .any(|(index, cell)| cells.clone().skip(index + 1).any(|other| other == cell))
+}
+
+/// Checks a thermometer, where the digits must strictly increase from the first cell.
+fn is_thermometer_consistent(grid: &Grid, cells: &[GridPos]) -> bool {
+    let len = cells.len();
+
+    // (index along the thermometer, digit) of the last filled cell.
+    let mut last_filled: Option<(usize, u8)> = None;
+
+    for (index, (row, col)) in cells.iter().enumerate() {
+        let digit = grid[*row][*col];
+        if digit == 0 {
+            continue;
+        }
+
+        // Leave enough room for the cells before and after this one.
+        if (digit as usize) < index + 1 || (digit as usize) > 9 - (len - 1 - index) {
+            return false;
+        }
+
+        if let Some((last_index, last_digit)) = last_filled
+            && (digit as usize) < last_digit as usize + (index - last_index)
+        {
+            return false;
+        }
+
+        last_filled = Some((index, digit));
+    }
+
+    true
+}
+
+/// Checks an arrow, where the digits along the arrow must sum to the number in the circle.
+fn is_arrow_consistent(grid: &Grid, circle: &[GridPos], arrow: &[GridPos]) -> bool {
+    let digit = |(row, col): &GridPos| u32::from(grid[*row][*col]);
+
+    let (arrow_sum, arrow_blanks) = arrow
+        .iter()
+        .map(digit)
+        .fold((0, 0), |(sum, blanks), digit| {
+            (sum + digit, blanks + u32::from(digit == 0))
+        });
+    let min_sum = arrow_sum + arrow_blanks;
+    let max_sum = arrow_sum + arrow_blanks * 9;
+
+    // Treat blanks in the circle as 1 (for the smallest value) or 9 (for the largest value).
+    let circle_value = |blank: u32| {
+        circle.iter().map(digit).fold(0, |value, digit| {
+            value * 10 + if digit == 0 { blank } else { digit }
+        })
+    };
+    let min_circle = circle_value(1);
+    let max_circle = circle_value(9);
+
+    min_sum <= max_circle && max_sum >= min_circle
+}
+
+/// Checks a sandwich, where the digits between the 1 and the 9 in a line must sum to sum.
+fn is_sandwich_consistent(grid: &Grid, line: Line, sum: u8) -> bool {
+    let digits = line.cells().map(|(row, col)| grid[row][col]);
+
+    let (Some(one), Some(nine)) = (
+        digits.iter().position(|digit| *digit == 1),
+        digits.iter().position(|digit| *digit == 9),
+    ) else {
+        return true;
+    };
+
+    let between = &digits[one.min(nine) + 1..one.max(nine)];
+    let filled_sum = between.iter().map(|digit| *digit as u32).sum::<u32>();
+    let blanks = between.iter().filter(|digit| **digit == 0).count() as u32;
+
+    // Digits between the 1 and the 9 can only be 2..=8.
+    filled_sum + blanks * 2 <= sum as u32 && filled_sum + blanks * 8 >= sum as u32
+}
+
+/// Checks a pair of cells, where the digits must satisfy holds once both cells are filled in.
+///
+/// If only one cell is filled in, then there must be some digit for the other cell that satisfies
+/// holds. Adjacent cells always share a row or col, so the other digit must also be different.
+fn is_pair_consistent(grid: &Grid, cells: &[GridPos; 2], holds: impl Fn(u8, u8) -> bool) -> bool {
+    let [(row_a, col_a), (row_b, col_b)] = *cells;
+
+    match (grid[row_a][col_a], grid[row_b][col_b]) {
+        (0, 0) => true,
+        (a, 0) => (1..=9).any(|b| b != a && holds(a, b)),
+        (0, b) => (1..=9).any(|a| a != b && holds(a, b)),
+        (a, b) => holds(a, b),
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use alloc::vec;
+
+    use fastrand::Rng;
+
+    use super::*;
+
+    use crate::sudoku::{
+        generate_with_edge_clues_and_rng, has_unique_solution_with_constraints,
+        solve_with_constraints,
+    };
+
+    const SOLUTION: Grid = Grid::new([
+        [5, 3, 4, 6, 7, 8, 9, 1, 2],
+        [6, 7, 2, 1, 9, 5, 3, 4, 8],
+        [1, 9, 8, 3, 4, 2, 5, 6, 7],
+        [8, 5, 9, 7, 6, 1, 4, 2, 3],
+        [4, 2, 6, 8, 5, 3, 7, 9, 1],
+        [7, 1, 3, 9, 2, 4, 8, 5, 6],
+        [9, 6, 1, 5, 3, 7, 2, 8, 4],
+        [2, 8, 7, 4, 1, 9, 6, 3, 5],
+        [3, 4, 5, 2, 8, 6, 1, 7, 9],
+    ])
+    .unwrap();
+
+    #[test]
+    fn check_thermometer() {
+        // 5 -> 6 -> 7, then 6 -> 5 is broken.
+        let thermometer = Constraint::Thermometer {
+            cells: vec![(0, 0), (1, 0), (1, 1)],
+        };
+        assert!(thermometer.is_consistent(&SOLUTION));
+
+        let thermometer = Constraint::Thermometer {
+            cells: vec![(1, 0), (0, 0)],
+        };
+        assert!(!thermometer.is_consistent(&SOLUTION));
+
+        // A bulb of 8 leaves no room for the two cells after it.
+        let mut grid = Grid::EMPTY;
+        grid.set((0, 0), 8);
+        let thermometer = Constraint::Thermometer {
+            cells: vec![(0, 0), (0, 1), (0, 2)],
+        };
+        assert!(!thermometer.is_consistent(&grid));
+
+        // 2 _ 3 cannot fit a digit in between.
+        let mut grid = Grid::EMPTY;
+        grid.set((0, 0), 2);
+        grid.set((0, 2), 3);
+        assert!(!thermometer.is_consistent(&grid));
+    }
+
+    #[test]
+    fn check_arrow() {
+        // 9 = 6 + 3.
+        let arrow = Constraint::Arrow {
+            circle: vec![(0, 6)],
+            arrow: vec![(0, 3), (0, 1)],
+        };
+        assert!(arrow.is_consistent(&SOLUTION));
+
+        // 12 = 4 + 8.
+        let arrow = Constraint::Arrow {
+            circle: vec![(0, 7), (0, 8)],
+            arrow: vec![(0, 2), (0, 5)],
+        };
+        assert!(arrow.is_consistent(&SOLUTION));
+
+        let arrow = Constraint::Arrow {
+            circle: vec![(0, 0)],
+            arrow: vec![(0, 1), (0, 2)],
+        };
+        assert!(!arrow.is_consistent(&SOLUTION));
+
+        // 3 in the circle cannot be reached by 2 + (at least 2).
+        let mut grid = Grid::EMPTY;
+        grid.set((0, 0), 3);
+        grid.set((0, 1), 2);
+        let arrow = Constraint::Arrow {
+            circle: vec![(0, 0)],
+            arrow: vec![(0, 1), (1, 1), (2, 1)],
+        };
+        assert!(arrow.is_well_formed() && !arrow.is_consistent(&grid));
+
+        // The circle and the arrow cannot share a cell.
+        let arrow = Constraint::Arrow {
+            circle: vec![(0, 0)],
+            arrow: vec![(0, 1), (0, 0)],
+        };
+        assert!(!arrow.is_well_formed());
+    }
+
+    #[test]
+    fn check_sandwich() {
+        // Row 0: 5 3 4 6 7 8 9 1 2, nothing between the 9 and the 1.
+        assert!(
+            Constraint::Sandwich {
+                line: Line::Row(0),
+                sum: 0
+            }
+            .is_consistent(&SOLUTION)
+        );
+
+        // Col 0: 5 6 1 8 4 7 9 2 3, 8 + 4 + 7 between the 1 and the 9.
+        assert!(
+            Constraint::Sandwich {
+                line: Line::Col(0),
+                sum: 19
+            }
+            .is_consistent(&SOLUTION)
+        );
+        assert!(
+            !Constraint::Sandwich {
+                line: Line::Col(0),
+                sum: 20
+            }
+            .is_consistent(&SOLUTION)
+        );
+    }
+
+    #[test]
+    fn check_edge_clues() {
+        // Row 0: 5 3 4 6 7 8 9 1 2, col 0: 5 6 1 8 4 7 9 2 3.
+        assert!(
+            Constraint::WhiteDot {
+                cells: [(0, 1), (0, 2)]
+            }
+            .is_consistent(&SOLUTION)
+        );
+        assert!(
+            !Constraint::WhiteDot {
+                cells: [(0, 0), (0, 1)]
+            }
+            .is_consistent(&SOLUTION)
+        );
+        assert!(
+            !Constraint::BlackDot {
+                cells: [(0, 1), (1, 1)]
+            }
+            .is_consistent(&SOLUTION)
+        );
+        assert!(
+            Constraint::BlackDot {
+                cells: [(3, 0), (4, 0)]
+            }
+            .is_consistent(&SOLUTION)
+        );
+        assert!(
+            Constraint::X {
+                cells: [(0, 6), (0, 7)]
+            }
+            .is_consistent(&SOLUTION)
+        );
+        let v = Constraint::V {
+            cells: [(0, 7), (1, 7)],
+        };
+        assert!(v.is_well_formed() && v.is_consistent(&SOLUTION));
+        let v = Constraint::V {
+            cells: [(0, 0), (0, 1)],
+        };
+        assert!(v.is_well_formed() && !v.is_consistent(&SOLUTION));
+        assert!(
+            Constraint::GreaterThan {
+                cells: [(0, 0), (0, 1)]
+            }
+            .is_consistent(&SOLUTION)
+        );
+        assert!(
+            !Constraint::GreaterThan {
+                cells: [(0, 1), (0, 0)]
+            }
+            .is_consistent(&SOLUTION)
+        );
+
+        // Nothing can sit next to a 5 with an X, or be smaller than a 1.
+        let mut grid = Grid::EMPTY;
+        grid.set((0, 0), 5);
+        grid.set((1, 1), 1);
+        assert!(
+            !Constraint::X {
+                cells: [(0, 0), (0, 1)]
+            }
+            .is_consistent(&grid)
+        );
+        assert!(
+            !Constraint::V {
+                cells: [(0, 0), (0, 1)]
+            }
+            .is_consistent(&grid)
+        );
+        assert!(
+            Constraint::WhiteDot {
+                cells: [(0, 0), (0, 1)]
+            }
+            .is_consistent(&grid)
+        );
+        assert!(
+            !Constraint::GreaterThan {
+                cells: [(1, 1), (1, 2)]
+            }
+            .is_consistent(&grid)
+        );
+        assert!(
+            Constraint::GreaterThan {
+                cells: [(1, 2), (1, 1)]
+            }
+            .is_consistent(&grid)
+        );
+
+        // Edge clues must be between adjacent cells.
+        assert!(
+            Constraint::X {
+                cells: [(0, 0), (1, 0)]
+            }
+            .is_well_formed()
+        );
+        assert!(
+            !Constraint::X {
+                cells: [(0, 0), (1, 1)]
+            }
+            .is_well_formed()
+        );
+        assert!(
+            !Constraint::X {
+                cells: [(8, 8), (8, 9)]
+            }
+            .is_well_formed()
+        );
+    }
+
+    #[test]
+    fn check_generate_with_edge_clues() {
+        let (puzzle, constraints) = generate_with_edge_clues_and_rng(&mut Rng::with_seed(1));
+        assert!(
+            constraints
+                .iter()
+                .all(|constraint| constraint.edge().is_some())
+        );
+
+        let solutions = solve_with_constraints(puzzle, &constraints);
+        assert_eq!(solutions.len(), 1);
+        assert!(validate(&solutions[0], &constraints).is_empty());
+
+        // The edge clues can carry all of the puzzle, in which case they alone have a unique
+        // solution.
+        assert_eq!(puzzle, Grid::EMPTY);
+        assert!(has_unique_solution_with_constraints(
+            Grid::EMPTY,
+            &constraints
+        ));
+    }
+
+    #[test]
+    fn check_validate() {
+        let constraints = [
+            Constraint::Thermometer {
+                cells: vec![(0, 0), (1, 0)],
+            },
+            Constraint::Thermometer {
+                cells: vec![(1, 0), (0, 0)],
+            },
+            Constraint::Sandwich {
+                line: Line::Row(9),
+                sum: 0,
+            },
+        ];
+        assert_eq!(
+            validate(&SOLUTION, &constraints),
+            vec![Violation::Clue(1), Violation::Clue(2)]
+        );
+
+        let mut grid = SOLUTION;
+        grid.set((0, 0), 3);
+        assert_eq!(
+            validate(&grid, &[]),
+            vec![Violation::Row(0), Violation::Col(0), Violation::Square(0)]
+        );
+    }
+
+    #[test]
+    fn check_variant_solve() {
+        // Blank out the top band, and let sandwiches and a thermometer pin it back down.
+        let mut puzzle = SOLUTION;
+        for pos in (0..27).map(|index| (index / 9, index % 9)) {
+            puzzle.set(pos, 0);
+        }
+
+        let constraints = [
+            Constraint::Sandwich {
+                line: Line::Row(0),
+                sum: 0,
+            },
+            Constraint::Sandwich {
+                line: Line::Row(1),
+                sum: 0,
+            },
+            Constraint::Thermometer {
+                cells: vec![(0, 1), (0, 2), (0, 3), (0, 4), (0, 5)],
+            },
+            Constraint::Arrow {
+                circle: vec![(2, 1)],
+                arrow: vec![(2, 3), (2, 4), (2, 5)],
+            },
+        ];
+
+        let solutions = solve_with_constraints(puzzle, &constraints);
+        assert!(solutions.contains(&SOLUTION));
+        assert!(
+            solutions
+                .iter()
+                .all(|solution| validate(solution, &constraints).is_empty())
+        );
+
+        assert_eq!(
+            has_unique_solution_with_constraints(puzzle, &constraints),
+            solutions.len() == 1
+        );
+    }
+}
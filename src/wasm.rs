@@ -0,0 +1,181 @@
+//! JavaScript bindings, built with the `wasm` feature for `wasm32-unknown-unknown`, see the
+//! WebAssembly section of the README.
+//!
+//! Grids are 81-char strings in reading order, with `.` (or `0`) for blanks, see [Grid]'s
+//! `FromStr`. Cells are given as indexes into those strings, i.e. `row * 9 + col`.
+//!
+//! On other targets, these are plain Rust functions, so they can be tested anywhere, including
+//! under a wasm runtime, see `tests/wasm.rs`.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use fastrand::Rng;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    rating::{Difficulty, hint as find_hint},
+    sudoku::{
+        Budget, Grid, GridPos, InputError, generate_with_budget,
+        generate_with_difficulty_and_budget, has_conflict, solve_any,
+    },
+    variant::validate,
+};
+
+/// The most grids to visit when generating a puzzle, which is about ten seconds natively and
+/// longer in a browser. There is no clock to time out with on `wasm32-unknown-unknown`, and a
+/// difficulty that is hard to hit could otherwise keep the tab busy forever.
+const GENERATE_MAX_NODES: u64 = 100_000_000;
+
+/// An error, which becomes a JavaScript `Error`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WasmError(String);
+
+impl Display for WasmError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<InputError> for WasmError {
+    fn from(error: InputError) -> Self {
+        WasmError(error.to_string())
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+impl From<WasmError> for JsValue {
+    fn from(error: WasmError) -> Self {
+        JsError::new(&error.0).into()
+    }
+}
+
+/// A digit that can be filled in by logic, see [hint].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    all(target_arch = "wasm32", target_os = "unknown"),
+    wasm_bindgen(getter_with_clone)
+)]
+pub struct Hint {
+    /// The cell to fill in.
+    pub index: usize,
+    pub digit: u8,
+
+    /// The hardest technique needed, e.g. `"naked single"`.
+    pub technique: String,
+
+    /// The cells that show why the digit goes there.
+    pub cells: Vec<usize>,
+
+    /// The hint as a sentence, e.g. `"5 at row 5, col 5 (naked single)"`.
+    pub text: String,
+}
+
+/// The result of [check].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    all(target_arch = "wasm32", target_os = "unknown"),
+    wasm_bindgen(getter_with_clone)
+)]
+pub struct Check {
+    /// Whether no digit is repeated in a row, col or square.
+    pub valid: bool,
+
+    /// Whether every cell is filled in.
+    pub complete: bool,
+
+    /// The cells with a digit that is repeated in their row, col or square.
+    pub conflicts: Vec<usize>,
+
+    /// The rules that are broken, e.g. `"row 1 has a repeated digit"`.
+    pub violations: Vec<String>,
+}
+
+/// Generates a puzzle with a unique solution, of any difficulty if difficulty is undefined.
+///
+/// The random numbers come from seed, e.g. from `crypto.getRandomValues()`, since there is no
+/// source of them in the browser otherwise. The same seed always gives the same puzzle, as it does
+/// for the C API and the Python module.
+///
+/// Gives up with an error if no puzzle is found after a while, which can happen with expert.
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), wasm_bindgen)]
+pub fn generate(seed: u64, difficulty: Option<String>) -> Result<String, WasmError> {
+    generate_with_max_nodes(seed, difficulty, GENERATE_MAX_NODES)
+}
+
+/// Generates a puzzle as [generate] does, visiting at most max_nodes grids.
+fn generate_with_max_nodes(
+    seed: u64,
+    difficulty: Option<String>,
+    max_nodes: u64,
+) -> Result<String, WasmError> {
+    let mut rng = Rng::with_seed(seed);
+    let budget = Budget {
+        max_nodes: Some(max_nodes),
+        ..Default::default()
+    };
+
+    let puzzle = match difficulty {
+        Some(difficulty) => generate_with_difficulty_and_budget(
+            Difficulty::from_str(&difficulty)?,
+            &mut rng,
+            &budget,
+        ),
+        None => generate_with_budget(&mut rng, &budget),
+    }
+    .map_err(|_| WasmError("Timed out: no puzzle was found in time.".to_string()))?;
+    Ok(puzzle.to_string())
+}
+
+/// Solves a puzzle, or gives undefined if it has no solution. If the puzzle has more than one
+/// solution, any of them is given.
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), wasm_bindgen)]
+pub fn solve(puzzle: &str) -> Result<Option<String>, WasmError> {
+    Ok(solve_any(Grid::from_str(puzzle)?).map(|solution| solution.to_string()))
+}
+
+/// Finds the next digit that can be filled in without guessing, or gives undefined if there is
+/// none.
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), wasm_bindgen)]
+pub fn hint(puzzle: &str) -> Result<Option<Hint>, WasmError> {
+    let to_index = |(row, col): GridPos| row * 9 + col;
+
+    Ok(find_hint(Grid::from_str(puzzle)?).map(|hint| Hint {
+        index: to_index(hint.pos),
+        digit: hint.digit,
+        technique: hint.technique.to_string(),
+        cells: hint.cells.iter().copied().map(to_index).collect(),
+        text: hint.to_string(),
+    }))
+}
+
+/// Verifies a partly filled in puzzle, e.g. while it is being played.
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), wasm_bindgen)]
+pub fn check(puzzle: &str) -> Result<Check, WasmError> {
+    let puzzle = Grid::from_str(puzzle)?;
+    let violations = validate(&puzzle, &[]);
+
+    Ok(Check {
+        valid: violations.is_empty(),
+        complete: puzzle.cells().all(|digit| digit != 0),
+        conflicts: (0..81)
+            .filter(|&index| has_conflict(&puzzle, (index / 9, index % 9)))
+            .collect(),
+        violations: violations.iter().map(ToString::to_string).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_generate_with_max_nodes() {
+        let error = generate_with_max_nodes(42, Some("expert".into()), 1000).unwrap_err();
+        assert!(error.to_string().contains("Timed out"), "{error}");
+        assert!(generate_with_max_nodes(42, None, 1000).is_err());
+    }
+}
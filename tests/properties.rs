@@ -0,0 +1,248 @@
+//! Checks invariants of solving, generating and the file formats over many seeded random puzzles.
+//!
+//! Every case is made from a seed, which is given in the message of a failing assertion. Set
+//! `SUDOKU_SEED` to run only that seed again, e.g. `SUDOKU_SEED=7 cargo test --test properties`.
+
+#![cfg(feature = "std")]
+
+use std::{env, path::PathBuf};
+
+use sudoku::prelude::{
+    FileFormat, Grid, Metadata, Puzzle, PuzzleRecord, Rng, Solution, count_solutions,
+    generate_with_rng, read_puzzles, solve, solve_any, write_puzzles,
+};
+
+/// The number of seeds to check, unless `SUDOKU_SEED` is set.
+const CASES: u64 = 20;
+
+/// The seeds to check, either `SUDOKU_SEED` or 0 to [CASES].
+fn seeds() -> Vec<u64> {
+    match env::var("SUDOKU_SEED") {
+        Ok(seed) => vec![seed.parse().expect("SUDOKU_SEED should be a number")],
+        Err(_) => (0..CASES).collect(),
+    }
+}
+
+/// Blanks up to count random givens of a puzzle, so that it may have more than one solution.
+fn blank_givens(mut puzzle: Puzzle, count: usize, rng: &mut Rng) -> Puzzle {
+    for _ in 0..count {
+        puzzle.set((rng.usize(0..9), rng.usize(0..9)), 0);
+    }
+
+    puzzle
+}
+
+/// Whether every given of puzzle is in the same cell of solution.
+fn keeps_givens(puzzle: Puzzle, solution: Solution) -> bool {
+    puzzle
+        .cells()
+        .zip(solution.cells())
+        .all(|(given, digit)| given == 0 || given == digit)
+}
+
+/// A change of a grid that keeps it valid: relabelling the digits, swapping rows within a band,
+/// swapping bands, the same for cols and stacks, and transposing.
+struct Transform {
+    digits: [u8; 10],
+    rows: [usize; 9],
+    cols: [usize; 9],
+    transpose: bool,
+}
+
+impl Transform {
+    fn random(rng: &mut Rng) -> Self {
+        let mut digits = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        rng.shuffle(&mut digits[1..]);
+
+        Self {
+            digits,
+            rows: Self::random_lines(rng),
+            cols: Self::random_lines(rng),
+            transpose: rng.bool(),
+        }
+    }
+
+    /// An order of the 9 rows (or cols) that keeps every band (or stack) together.
+    fn random_lines(rng: &mut Rng) -> [usize; 9] {
+        let mut bands = [0, 1, 2];
+        rng.shuffle(&mut bands);
+
+        let mut lines = [0; 9];
+        for (index, band) in bands.into_iter().enumerate() {
+            let mut offsets = [0, 1, 2];
+            rng.shuffle(&mut offsets);
+            for (line, offset) in lines[index * 3..index * 3 + 3].iter_mut().zip(offsets) {
+                *line = band * 3 + offset;
+            }
+        }
+
+        lines
+    }
+
+    fn apply(&self, grid: Grid) -> Grid {
+        let mut transformed = Grid::EMPTY;
+        for row in 0..9 {
+            for col in 0..9 {
+                let (from_row, from_col) = if self.transpose {
+                    (self.cols[col], self.rows[row])
+                } else {
+                    (self.rows[row], self.cols[col])
+                };
+                transformed.set(
+                    (row, col),
+                    self.digits[usize::from(grid[from_row][from_col])],
+                );
+            }
+        }
+
+        transformed
+    }
+}
+
+#[test]
+fn check_generate_is_unique() {
+    for seed in seeds() {
+        let puzzle = generate_with_rng(&mut Rng::with_seed(seed));
+        assert_eq!(count_solutions(puzzle, 2), 1, "seed {seed}: {puzzle}");
+
+        // The same seed gives the same puzzle, so that a failure can be reproduced.
+        assert_eq!(generate_with_rng(&mut Rng::with_seed(seed)), puzzle);
+    }
+}
+
+#[test]
+fn check_solution_keeps_givens() {
+    for seed in seeds() {
+        let rng = &mut Rng::with_seed(seed);
+        let puzzle = blank_givens(generate_with_rng(rng), 4, rng);
+
+        let solutions = solve(puzzle);
+        assert!(!solutions.is_empty(), "seed {seed}: {puzzle}");
+        for solution in solutions {
+            assert!(solution.cells().all(|digit| digit != 0));
+            assert!(keeps_givens(puzzle, solution), "seed {seed}: {puzzle}");
+        }
+    }
+}
+
+#[test]
+fn check_solve_agrees_with_solve_any() {
+    for seed in seeds() {
+        let rng = &mut Rng::with_seed(seed);
+        let puzzle = generate_with_rng(rng);
+        assert_eq!(
+            solve(puzzle),
+            Vec::from_iter(solve_any(puzzle)),
+            "seed {seed}: {puzzle}"
+        );
+
+        let puzzle = blank_givens(puzzle, 4, rng);
+        let solutions = solve(puzzle);
+        let solution = solve_any(puzzle).unwrap();
+        assert!(solutions.contains(&solution), "seed {seed}: {puzzle}");
+        assert_eq!(
+            solutions.len(),
+            count_solutions(puzzle, usize::MAX),
+            "seed {seed}: {puzzle}"
+        );
+
+        // A conflict has no solution.
+        let mut puzzle = solution;
+        puzzle.set((0, 0), puzzle[0][1]);
+        assert_eq!(solve(puzzle), vec![], "seed {seed}: {puzzle}");
+        assert_eq!(solve_any(puzzle), None, "seed {seed}: {puzzle}");
+    }
+}
+
+#[test]
+fn check_transforms_commute_with_solving() {
+    for seed in seeds() {
+        let rng = &mut Rng::with_seed(seed);
+        let puzzle = generate_with_rng(rng);
+        let transform = Transform::random(rng);
+
+        assert_eq!(
+            solve(transform.apply(puzzle)),
+            vec![transform.apply(solve_any(puzzle).unwrap())],
+            "seed {seed}: {puzzle}"
+        );
+    }
+}
+
+#[test]
+fn check_formats_round_trip() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("properties.txt");
+
+    for seed in seeds() {
+        let rng = &mut Rng::with_seed(seed);
+        let records = [
+            generate_with_rng(rng),
+            blank_givens(generate_with_rng(rng), 4, rng),
+            Grid::EMPTY,
+        ]
+        .map(|puzzle| PuzzleRecord {
+            puzzle,
+            metadata: Metadata {
+                author: Some(format!("seed {seed}")),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        for record in &records {
+            let grids = [
+                (false, "."),
+                (false, "0"),
+                (false, " "),
+                (true, " "),
+                (true, "_"),
+            ];
+            for (border, blank_char) in grids {
+                write_puzzles(
+                    std::slice::from_ref(record),
+                    Some(&path),
+                    FileFormat::Grid,
+                    border,
+                    blank_char,
+                )
+                .unwrap();
+                let read = read_puzzles(Some(&path), Some(FileFormat::Grid), true).unwrap();
+                assert_eq!(
+                    read.value[0].puzzle, record.puzzle,
+                    "seed {seed}: grid with border {border} and blank {blank_char:?}"
+                );
+            }
+
+            let formats = [
+                FileFormat::Sdk,
+                FileFormat::Ss,
+                FileFormat::Variant,
+                FileFormat::Fpuzzles,
+            ];
+            for format in formats {
+                write_puzzles(
+                    std::slice::from_ref(record),
+                    Some(&path),
+                    format,
+                    false,
+                    ".",
+                )
+                .unwrap();
+                let read = read_puzzles(Some(&path), Some(format), true).unwrap();
+                assert_eq!(read.value.len(), 1, "seed {seed}: {format}");
+                assert_eq!(read.value[0].puzzle, record.puzzle, "seed {seed}: {format}");
+            }
+        }
+
+        write_puzzles(&records, Some(&path), FileFormat::Sdm, false, ".").unwrap();
+        let read = read_puzzles(Some(&path), Some(FileFormat::Sdm), true).unwrap();
+        assert_eq!(
+            read.value
+                .iter()
+                .map(|record| record.puzzle)
+                .collect::<Vec<_>>(),
+            records.map(|record| record.puzzle),
+            "seed {seed}: sdm"
+        );
+    }
+}
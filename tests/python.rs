@@ -0,0 +1,55 @@
+//! Runs `tests/test_python.py` against the `sudoku` Python module.
+//!
+//! Needs the `python` feature, and the interpreter PyO3 was built for, `python3` or the one in
+//! `PYO3_PYTHON`.
+
+#![cfg(all(unix, feature = "python"))]
+
+use std::{env, fs, path::PathBuf, process::Command};
+
+#[test]
+fn check_python_module() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // The test binary is in target/<profile>/deps, and the shared library in target/<profile>.
+    let lib_dir = env::current_exe()
+        .unwrap()
+        .parent()
+        .and_then(|deps| deps.parent())
+        .unwrap()
+        .to_path_buf();
+
+    // cargo test only builds the rlib, so build the shared library as well, as maturin does.
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo.current_dir(&manifest_dir).args([
+        "rustc",
+        "--lib",
+        "--crate-type",
+        "cdylib",
+        "--features",
+        "python",
+    ]);
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    assert!(
+        cargo.status().unwrap().success(),
+        "cannot build the library"
+    );
+
+    // Python imports the module by its file name.
+    let module_dir = lib_dir.join("python");
+    fs::create_dir_all(&module_dir).unwrap();
+    fs::copy(lib_dir.join("libsudoku.so"), module_dir.join("sudoku.so")).unwrap();
+
+    let python = env::var("PYO3_PYTHON").unwrap_or("python3".into());
+    let output = Command::new(&python)
+        .arg(manifest_dir.join("tests/test_python.py"))
+        .env("PYTHONPATH", &module_dir)
+        .output()
+        .unwrap_or_else(|error| panic!("cannot run {python}: {error}"));
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,86 @@
+"""Exercises the `sudoku` Python module, run by tests/python.rs with the module on PYTHONPATH."""
+
+import os
+import tempfile
+import unittest
+
+import sudoku
+
+PUZZLE = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79"
+SOLUTION = "534678912672195348198342567859761423426853791713924856961537284287419635345286179"
+
+
+def rows(text):
+    return [[0 if c == "." else int(c) for c in text[row * 9 : row * 9 + 9]] for row in range(9)]
+
+
+class TestSudoku(unittest.TestCase):
+    def test_solve(self):
+        self.assertEqual(sudoku.solve_any(PUZZLE), rows(SOLUTION))
+        self.assertEqual(sudoku.solve(rows(PUZZLE)), [rows(SOLUTION)])
+        self.assertEqual(sudoku.solve_any(sum(rows(PUZZLE), [])), rows(SOLUTION))
+        self.assertIsNone(sudoku.solve_any("55" + PUZZLE[2:]))
+
+    def test_count_and_rate(self):
+        self.assertEqual(sudoku.count_solutions(PUZZLE), 1)
+        self.assertEqual(sudoku.count_solutions("." * 81, limit=5), 5)
+        self.assertEqual(sudoku.rate(PUZZLE), "Easy")
+
+    def test_timeout(self):
+        self.assertEqual(sudoku.solve_any(PUZZLE, timeout=10), rows(SOLUTION))
+        with self.assertRaises(TimeoutError):
+            sudoku.solve("." * 81, timeout=0)
+        with self.assertRaises(TimeoutError):
+            sudoku.solve_any("." * 81, timeout=0)
+        with self.assertRaises(TimeoutError):
+            sudoku.count_solutions("." * 81, limit=10**9, timeout=0.01)
+        with self.assertRaises(ValueError):
+            sudoku.count_solutions(PUZZLE, timeout=-1)
+
+    def test_generate(self):
+        puzzle = sudoku.generate(seed=42)
+        self.assertEqual(puzzle, sudoku.generate(seed=42))
+        self.assertEqual(sudoku.count_solutions(puzzle, limit=2), 1)
+        self.assertEqual(sudoku.rate(sudoku.generate("easy", seed=7)), "Easy")
+        with self.assertRaises(ValueError):
+            sudoku.generate("impossible")
+        with self.assertRaises(TimeoutError):
+            sudoku.generate("expert", seed=42, timeout=0)
+
+    def test_invalid_grids(self):
+        for grid in ["123", [[10] * 9] * 9, [[0] * 8] * 9, [0] * 80, None]:
+            with self.assertRaises(ValueError):
+                sudoku.solve_any(grid)
+
+    def test_read(self):
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "puzzle.txt")
+            with open(path, "w") as file:
+                file.write(PUZZLE.replace("5", "l", 1))
+
+            with self.assertWarns(UserWarning):
+                grid = sudoku.read_grid(path)
+            self.assertEqual(grid[0][0], 0)
+            with self.assertRaises(ValueError):
+                sudoku.read_grid(path, strict=True)
+
+            with self.assertWarns(UserWarning):
+                [record] = sudoku.read_puzzles(path, format="grid")
+            self.assertEqual(record["puzzle"][0][1], 3)
+            self.assertEqual(record["constraints"], [])
+            self.assertIsNone(record["metadata"]["author"])
+
+        with self.assertRaises(OSError):
+            sudoku.read_puzzles(os.path.join(directory, "missing.sdm"))
+
+    @unittest.skipUnless(__import__("importlib").util.find_spec("numpy"), "needs numpy")
+    def test_numpy(self):
+        import numpy
+
+        grid = numpy.array(rows(PUZZLE), dtype=numpy.uint8)
+        self.assertEqual(numpy.array(sudoku.solve_any(grid)).tolist(), rows(SOLUTION))
+        self.assertEqual(sudoku.solve_any(grid.flatten()), rows(SOLUTION))
+
+
+if __name__ == "__main__":
+    unittest.main()
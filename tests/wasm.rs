@@ -0,0 +1,65 @@
+//! Tests the JavaScript bindings as plain Rust functions, natively or under a wasm runtime, e.g.
+//! `cargo test --target wasm32-wasip1 --no-default-features --features wasm --test wasm` with
+//! wasmtime installed, see `.cargo/config.toml`.
+
+#![cfg(feature = "wasm")]
+
+use sudoku::wasm::{check, generate, hint, solve};
+
+const PUZZLE: &str =
+    "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+const SOLUTION: &str =
+    "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+#[test]
+fn check_generate() {
+    let puzzle = generate(42, None).unwrap();
+    assert_eq!(puzzle.len(), 81);
+    assert_eq!(generate(42, None).unwrap(), puzzle);
+    assert_ne!(generate(43, None).unwrap(), puzzle);
+    assert!(solve(&puzzle).unwrap().is_some());
+
+    assert!(generate(7, Some("easy".into())).is_ok());
+    assert!(generate(7, Some("impossible".into())).is_err());
+}
+
+#[test]
+fn check_solve() {
+    assert_eq!(solve(PUZZLE).unwrap().as_deref(), Some(SOLUTION));
+    assert_eq!(
+        solve(&PUZZLE.replace('.', "0")).unwrap().as_deref(),
+        Some(SOLUTION)
+    );
+    assert_eq!(solve(&format!("55{}", &PUZZLE[2..])).unwrap(), None);
+    assert!(
+        solve("123")
+            .unwrap_err()
+            .to_string()
+            .contains("exactly 81 cells")
+    );
+}
+
+#[test]
+fn check_hint() {
+    let result = hint(PUZZLE).unwrap().unwrap();
+    assert_eq!((result.index, result.digit), (40, 5));
+    assert_eq!(result.technique, "naked single");
+    assert_eq!(result.text, "5 at row 5, col 5 (naked single)");
+    assert_eq!(hint(SOLUTION).unwrap(), None);
+}
+
+#[test]
+fn check_check() {
+    let result = check(PUZZLE).unwrap();
+    assert!(result.valid && !result.complete);
+    assert!(result.conflicts.is_empty());
+
+    let result = check(&format!("55{}", &PUZZLE[2..])).unwrap();
+    assert!(!result.valid);
+    assert_eq!(result.conflicts, vec![0, 1]);
+    assert_eq!(result.violations[0], "row 1 has a repeated digit");
+
+    assert!(check(SOLUTION).unwrap().complete);
+    assert!(check(&"x".repeat(81)).is_err());
+}